@@ -2,16 +2,24 @@
 #![allow(clippy::new_without_default)]
 #![allow(dead_code)]
 
+// Native builds keep storages in thread locals, see `storage!`.
+#[cfg(not(target_arch = "wasm32"))]
+extern crate std;
+
 // TODO (sails): rename here to use `notifier`::`Notifier`/`Informer`.
 mod informer {
     pub use sails_rtl::gstd::events::GStdEventTrigger as Gstd;
 }
-use gstd::String;
+use gstd::{msg, String};
+use primitive_types::U256;
 use sails_macros::{gprogram, groute};
-use services::erc20;
+use services::{erc20, roles};
 
 pub mod services;
 
+#[cfg(feature = "testing")]
+pub mod testing;
+
 pub struct BreathxProgram;
 
 // TODO (sails): allow to import all necessary macros at once (gprogram, grout, etc).
@@ -19,8 +27,16 @@ pub struct BreathxProgram;
 #[gprogram]
 impl BreathxProgram {
     // TODO (sails): fix arguments are unused.
-    pub fn new(name: String, symbol: String, decimals: u8) -> Self {
-        <erc20::Service<()>>::seed(name, symbol, decimals);
+    pub fn new(name: String, symbol: String, decimals: u8, cap: Option<U256>) -> Self {
+        <erc20::Service<()>>::seed(name, symbol, decimals, cap);
+        <roles::Service<()>>::seed(msg::source());
+        Self
+    }
+
+    // Deploys token migrated from another program's exported state, given its first chunk.
+    pub fn from_state(chunk: erc20::StateChunk) -> Self {
+        <erc20::Service<()>>::seed_from_state(chunk);
+        <roles::Service<()>>::seed(msg::source());
         Self
     }
 
@@ -28,4 +44,9 @@ impl BreathxProgram {
     pub fn erc20(&self) -> erc20::Service<informer::Gstd<erc20::Event>> {
         erc20::Service::new(informer::Gstd::new())
     }
+
+    #[groute("roles")]
+    pub fn roles(&self) -> roles::Service<informer::Gstd<roles::Event>> {
+        roles::Service::new(informer::Gstd::new())
+    }
 }
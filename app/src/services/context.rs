@@ -0,0 +1,62 @@
+// Context of the message being processed, faked natively with `testing` feature.
+
+#[cfg(not(feature = "testing"))]
+pub use self::program::*;
+
+#[cfg(feature = "testing")]
+pub use crate::testing::context::{
+    block_height, block_timestamp, program_id, send_for_reply, send_value, send_with_reply_hook,
+    source, value,
+};
+
+#[cfg(not(feature = "testing"))]
+mod program {
+    use crate::services::panicking;
+    use gstd::{msg, ActorId, Vec};
+
+    pub use gstd::{
+        exec::{block_height, block_timestamp, program_id},
+        msg::{source, value},
+    };
+
+    // Sending value fails in place, e.g. if it's below existential deposit.
+    pub fn send_value(to: ActorId, value: u128) -> bool {
+        msg::send_bytes(to, b"", value).is_ok()
+    }
+
+    // Failed sends and error replies are both `None`.
+    pub async fn send_for_reply(to: ActorId, payload: Vec<u8>, value: u128) -> Option<Vec<u8>> {
+        msg::send_bytes_for_reply(to, payload, value, 0)
+            .ok()?
+            .await
+            .ok()
+    }
+
+    // Gas reserved for handling reply, so reply hook runs even if sender is gone.
+    const REPLY_DEPOSIT: u64 = 10_000_000_000;
+
+    // Hook is told whether reply is successful. It runs on reply before the sender resumes,
+    // even if the sender never does, e.g. being evicted from waitlist meanwhile.
+    // Failed sends return `false` at once, never running the hook.
+    pub async fn send_with_reply_hook(
+        to: ActorId,
+        payload: Vec<u8>,
+        value: u128,
+        on_reply: impl FnOnce(bool) + 'static,
+    ) -> bool {
+        let Ok(future) = msg::send_bytes_for_reply(to, payload, value, REPLY_DEPOSIT) else {
+            return false;
+        };
+
+        // Hook is only rejected without reply deposit, while panic reverts the send.
+        let future = panicking(move || {
+            future.handle_reply(move || {
+                on_reply(msg::reply_code().is_ok_and(|code| code.is_success()))
+            })
+        });
+
+        let _reply = future.await;
+
+        true
+    }
+}
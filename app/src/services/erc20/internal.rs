@@ -1,11 +1,45 @@
 pub use utils::*;
 
-pub type AllowancesMap = HashMap<(ActorId, ActorId), NonZeroU256>;
+// Allowance is stored with optional block it expires at.
+pub type AllowancesMap = HashMap<(ActorId, ActorId), (NonZeroU256, Option<u32>)>;
 pub type BalancesMap = HashMap<ActorId, NonZeroU256>;
+pub type BlocklistSet = HashSet<ActorId>;
+pub type LocksMap = HashMap<ActorId, Locks>;
+pub type NoncesMap = HashMap<ActorId, u64>;
+// Offset of the next expected chunk and meta of the first one, while import is in progress.
+pub type PendingImport = Option<(u32, StateMeta)>;
+pub type VestingMap = HashMap<ActorId, VestingSchedule>;
 pub type Result<T, E = Error> = core::result::Result<T, E>;
 
-use gstd::{collections::HashMap, prelude::*, ActorId};
-use primitive_types::U256;
+use blake2::Digest;
+use core::hash::{BuildHasher, Hash};
+use gstd::{
+    collections::{BTreeMap, HashMap, HashSet},
+    prelude::*,
+    ActorId,
+};
+use primitive_types::{U256, U512};
+
+// Upper bound for `batch_transfer*` to keep single message within gas limits.
+pub const MAX_BATCH_SIZE: usize = 256;
+
+// Upper bound for account's own locks and, separately, for ones it received,
+// so checking spendable balance stays cheap.
+pub const MAX_LOCKS: usize = 32;
+
+// Upper bound for paginated queries' `limit`.
+pub const MAX_PAGE_SIZE: u32 = 256;
+
+// Version of `StateChunk` format, bumped on any incompatible change.
+pub const STATE_VERSION: u8 = 1;
+
+// Methods called on receivers' service by `*_and_call` commands with `(owner, value, data)`
+// arguments. Receiver accepts by replying `(service, method, true)`, as sails service would.
+pub const RECEIVER_SERVICE: &str = "Erc20Receiver";
+pub const ON_APPROVAL_RECEIVED: &str = "OnApprovalReceived";
+pub const ON_TOKEN_RECEIVED: &str = "OnTokenReceived";
+
+const SR25519_SIGNING_CONTEXT: &[u8] = b"substrate";
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Encode, Decode, TypeInfo)]
 pub enum Event {
@@ -19,25 +53,411 @@ pub enum Event {
         to: ActorId,
         value: NonZeroU256,
     },
+    // `None` operation stands for global pause.
+    Paused {
+        operation: Option<Operation>,
+    },
+    Unpaused {
+        operation: Option<Operation>,
+    },
+    Frozen {
+        account: ActorId,
+    },
+    Unfrozen {
+        account: ActorId,
+    },
+    Seized {
+        from: ActorId,
+        to: ActorId,
+        value: NonZeroU256,
+    },
+    Deposit {
+        account: ActorId,
+        value: NonZeroU256,
+    },
+    Withdrawal {
+        account: ActorId,
+        value: NonZeroU256,
+    },
+    Snapshot {
+        id: u64,
+    },
+    DelegateChanged {
+        delegator: ActorId,
+        from_delegate: ActorId,
+        to_delegate: ActorId,
+    },
+    DelegateVotesChanged {
+        delegate: ActorId,
+        previous_votes: U256,
+        new_votes: U256,
+    },
+    VestingCreated {
+        beneficiary: ActorId,
+        total: NonZeroU256,
+    },
+    VestingReleased {
+        beneficiary: ActorId,
+        value: NonZeroU256,
+    },
+    VestingRevoked {
+        beneficiary: ActorId,
+        refund: U256,
+    },
+    Locked {
+        account: ActorId,
+        value: NonZeroU256,
+        until: u32,
+    },
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Encode, Decode, TypeInfo)]
 pub enum Error {
+    AccountFrozen,
+    AccountNotFrozen,
+    AllowanceExpired,
+    AllowanceUnderflow,
+    BalanceLocked,
+    BatchTooLarge,
+    CapExceeded,
+    FutureLookup,
+    ImportClosed,
     InsufficientAllowance,
     InsufficientBalance,
+    InvalidLock,
+    InvalidNonce,
+    InvalidSchedule,
+    InvalidSignature,
+    NotRevocable,
     NumericOverflow,
+    Paused,
+    PermitExpired,
+    ReceiverRejected,
+    SnapshotNotFound,
+    StateMismatch,
+    TooManyLocks,
+    Unauthorized,
+    UnsupportedVersion,
+    ValueSendFailed,
+    VestingExists,
+    VestingNotFound,
 }
 
-pub fn allowance(allowances: &AllowancesMap, owner: ActorId, spender: ActorId) -> U256 {
-    allowances
-        .get(&(owner, spender))
-        .cloned()
-        .map(Into::into)
+// Both hold `(until, value)` pairs. Locks received with `transfer_locked` take
+// their own slots, so senders can't exhaust ones the account locks itself.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Locks {
+    pub own: Vec<(u32, U256)>,
+    pub received: Vec<(u32, U256)>,
+}
+
+impl Locks {
+    pub fn is_empty(&self) -> bool {
+        self.own.is_empty() && self.received.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &(u32, U256)> {
+        self.own.iter().chain(&self.received)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Encode, Decode, TypeInfo)]
+pub enum Operation {
+    Approve,
+    Mint,
+    Transfer,
+}
+
+// SCALE-encoded form of this struct is the message signed by the owner.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Encode, Decode, TypeInfo)]
+pub struct Permit {
+    pub domain_separator: [u8; 32],
+    pub owner: ActorId,
+    pub spender: ActorId,
+    pub value: U256,
+    pub nonce: u64,
+    pub deadline: u64,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Encode, Decode, TypeInfo)]
+pub enum Signature {
+    Ed25519([u8; 64]),
+    Sr25519([u8; 64]),
+}
+
+// Histories hold `(id, value)` pairs, where value is the one at snapshot `id`,
+// recorded lazily on the first change after the snapshot was taken.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Snapshots {
+    pub current: u64,
+    pub balances: HashMap<ActorId, Vec<(u64, U256)>>,
+    pub total_supply: Vec<(u64, U256)>,
+}
+
+// Chunk of exported state: balances and allowances from `offset` on, along with meta.
+// Allowances are `(owner, spender, value, expiry block)`.
+#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode, TypeInfo)]
+pub struct StateChunk {
+    pub version: u8,
+    pub offset: u32,
+    pub meta: StateMeta,
+    pub balances: Vec<(ActorId, U256)>,
+    pub allowances: Vec<(ActorId, ActorId, U256, Option<u32>)>,
+    pub next: Option<u32>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode, TypeInfo)]
+pub struct StateMeta {
+    pub name: String,
+    pub symbol: String,
+    pub decimals: u8,
+    pub cap: Option<U256>,
+    pub total_supply: U256,
+}
+
+// Checkpoints hold `(block height, votes)` pairs, one per block at most.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Votes {
+    pub delegates: HashMap<ActorId, ActorId>,
+    pub checkpoints: HashMap<ActorId, Vec<(u32, U256)>>,
+}
+
+// Timestamps are in milliseconds, `cliff` and `duration` are counted from `start`.
+// Vested amount grows linearly after the cliff, so whole `total` vests at the end.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Encode, Decode, TypeInfo)]
+pub struct VestingSchedule {
+    pub beneficiary: ActorId,
+    pub total: U256,
+    pub released: U256,
+    pub start: u64,
+    pub cliff: u64,
+    pub duration: u64,
+    pub revocable: bool,
+}
+
+// Change of delegate's votes as `(delegate, previous votes, new votes)`.
+pub type VotesChange = (ActorId, U256, U256);
+
+// Key-value store, so balances and allowances could be backed by any map (or mock).
+pub trait Store<K, V> {
+    fn get(&self, key: &K) -> Option<&V>;
+
+    fn insert(&mut self, key: K, value: V) -> Option<V>;
+
+    fn remove(&mut self, key: &K) -> Option<V>;
+
+    fn len(&self) -> usize;
+
+    fn iter<'a>(&'a self) -> impl Iterator<Item = (&'a K, &'a V)>
+    where
+        K: 'a,
+        V: 'a;
+
+    fn contains_key(&self, key: &K) -> bool {
+        self.get(key).is_some()
+    }
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+pub trait AllowancesStore: Store<(ActorId, ActorId), (NonZeroU256, Option<u32>)> {}
+
+impl<T: Store<(ActorId, ActorId), (NonZeroU256, Option<u32>)>> AllowancesStore for T {}
+
+pub trait BalancesStore: Store<ActorId, NonZeroU256> {}
+
+impl<T: Store<ActorId, NonZeroU256>> BalancesStore for T {}
+
+impl<K: Eq + Hash, V, S: BuildHasher> Store<K, V> for HashMap<K, V, S> {
+    fn get(&self, key: &K) -> Option<&V> {
+        HashMap::get(self, key)
+    }
+
+    fn insert(&mut self, key: K, value: V) -> Option<V> {
+        HashMap::insert(self, key, value)
+    }
+
+    fn remove(&mut self, key: &K) -> Option<V> {
+        HashMap::remove(self, key)
+    }
+
+    fn len(&self) -> usize {
+        HashMap::len(self)
+    }
+
+    fn iter<'a>(&'a self) -> impl Iterator<Item = (&'a K, &'a V)>
+    where
+        K: 'a,
+        V: 'a,
+    {
+        HashMap::iter(self)
+    }
+}
+
+impl<K: Ord, V> Store<K, V> for BTreeMap<K, V> {
+    fn get(&self, key: &K) -> Option<&V> {
+        BTreeMap::get(self, key)
+    }
+
+    fn insert(&mut self, key: K, value: V) -> Option<V> {
+        BTreeMap::insert(self, key, value)
+    }
+
+    fn remove(&mut self, key: &K) -> Option<V> {
+        BTreeMap::remove(self, key)
+    }
+
+    fn len(&self) -> usize {
+        BTreeMap::len(self)
+    }
+
+    fn iter<'a>(&'a self) -> impl Iterator<Item = (&'a K, &'a V)>
+    where
+        K: 'a,
+        V: 'a,
+    {
+        BTreeMap::iter(self)
+    }
+}
+
+// Balances store recording snapshots and moving delegated votes on every write, so both
+// follow whatever mutates balances. `total_supply` is the one before mutation.
+pub struct TrackedBalances<'a, S> {
+    balances: &'a mut S,
+    snapshots: &'a mut Snapshots,
+    total_supply: U256,
+    votes: &'a mut Votes,
+    block: u32,
+    accounts: Vec<ActorId>,
+    changes: Vec<VotesChange>,
+}
+
+impl<'a, S: BalancesStore> TrackedBalances<'a, S> {
+    pub fn new(
+        balances: &'a mut S,
+        snapshots: &'a mut Snapshots,
+        total_supply: U256,
+        votes: &'a mut Votes,
+        block: u32,
+    ) -> Self {
+        Self {
+            balances,
+            snapshots,
+            total_supply,
+            votes,
+            block,
+            accounts: Vec::new(),
+            changes: Vec::new(),
+        }
+    }
+
+    // Returns written accounts and votes changes aggregated per delegate,
+    // omitting delegates whose votes ended up unchanged.
+    pub fn finish(self) -> (Vec<ActorId>, Vec<VotesChange>) {
+        let changes = self
+            .changes
+            .into_iter()
+            .filter(|(_, previous, new)| previous != new)
+            .collect();
+
+        (self.accounts, changes)
+    }
+
+    // Must precede the write, so pre-change balance is the stored one.
+    fn track(&mut self, account: ActorId, new: U256) {
+        record_snapshot(
+            self.snapshots,
+            &*self.balances,
+            self.total_supply,
+            [account],
+        );
+
+        let previous = balance_of(&*self.balances, account);
+        let delegate = delegates(self.votes, account);
+
+        let (src, dst, value) = if new > previous {
+            (ActorId::zero(), delegate, new - previous)
+        } else {
+            (delegate, ActorId::zero(), previous - new)
+        };
+
+        for (delegate, previous, new) in
+            move_delegate_votes(self.votes, src, dst, value, self.block)
+        {
+            match self.changes.iter_mut().find(|change| change.0 == delegate) {
+                Some(change) => change.2 = new,
+                None => self.changes.push((delegate, previous, new)),
+            }
+        }
+
+        if !self.accounts.contains(&account) {
+            self.accounts.push(account);
+        }
+    }
+}
+
+impl<S: BalancesStore> Store<ActorId, NonZeroU256> for TrackedBalances<'_, S> {
+    fn get(&self, key: &ActorId) -> Option<&NonZeroU256> {
+        self.balances.get(key)
+    }
+
+    fn insert(&mut self, key: ActorId, value: NonZeroU256) -> Option<NonZeroU256> {
+        self.track(key, value.into());
+        self.balances.insert(key, value)
+    }
+
+    fn remove(&mut self, key: &ActorId) -> Option<NonZeroU256> {
+        self.track(*key, U256::zero());
+        self.balances.remove(key)
+    }
+
+    fn len(&self) -> usize {
+        self.balances.len()
+    }
+
+    fn iter<'a>(&'a self) -> impl Iterator<Item = (&'a ActorId, &'a NonZeroU256)>
+    where
+        ActorId: 'a,
+        NonZeroU256: 'a,
+    {
+        self.balances.iter()
+    }
+}
+
+pub fn allowance(
+    allowances: &impl AllowancesStore,
+    owner: ActorId,
+    spender: ActorId,
+    now: u32,
+) -> U256 {
+    active_allowance(allowances, owner, spender, now)
+        .map(|(value, _)| value)
         .unwrap_or_default()
 }
 
+// Spenders are expected in stable order, so pages don't shift between calls.
+pub fn allowances_of(
+    allowances: &impl AllowancesStore,
+    owner: ActorId,
+    spenders: impl IntoIterator<Item = ActorId>,
+    offset: u32,
+    limit: u32,
+    now: u32,
+) -> Vec<(ActorId, U256)> {
+    spenders
+        .into_iter()
+        .filter_map(|spender| {
+            active_allowance(allowances, owner, spender, now).map(|(value, _)| (spender, value))
+        })
+        .skip(offset as usize)
+        .take(limit.min(MAX_PAGE_SIZE) as usize)
+        .collect()
+}
+
 pub fn approve(
-    allowances: &mut AllowancesMap,
+    allowances: &mut impl AllowancesStore,
     owner: ActorId,
     spender: ActorId,
     value: U256,
@@ -46,18 +466,35 @@ pub fn approve(
         return false;
     }
 
-    let key = (owner, spender);
+    set_allowance(allowances, owner, spender, value, None)
+}
 
-    let Ok(non_zero_value) = value.try_into() else {
-        return allowances.remove(&key).is_some();
-    };
+pub fn approve_with_expiry(
+    allowances: &mut impl AllowancesStore,
+    owner: ActorId,
+    spender: ActorId,
+    value: U256,
+    expires_at: u32,
+    now: u32,
+) -> Result<bool> {
+    if expires_at <= now {
+        return Err(Error::AllowanceExpired);
+    }
 
-    let prev = allowances.insert(key, non_zero_value);
+    if owner == spender {
+        return Ok(false);
+    }
 
-    prev.map(|v| v != non_zero_value).unwrap_or(true)
+    Ok(set_allowance(
+        allowances,
+        owner,
+        spender,
+        value,
+        Some(expires_at),
+    ))
 }
 
-pub fn balance_of(balances: &BalancesMap, owner: ActorId) -> U256 {
+pub fn balance_of(balances: &impl BalancesStore, owner: ActorId) -> U256 {
     balances
         .get(&owner)
         .cloned()
@@ -65,26 +502,97 @@ pub fn balance_of(balances: &BalancesMap, owner: ActorId) -> U256 {
         .unwrap_or_default()
 }
 
-pub fn transfer(
-    balances: &mut BalancesMap,
+pub fn balance_of_at(
+    snapshots: &Snapshots,
+    balances: &impl BalancesStore,
+    owner: ActorId,
+    id: u64,
+) -> Result<U256> {
+    ensure_snapshot_exists(snapshots, id)?;
+
+    let history = snapshots
+        .balances
+        .get(&owner)
+        .map(Vec::as_slice)
+        .unwrap_or_default();
+
+    Ok(value_at(history, id).unwrap_or_else(|| balance_of(balances, owner)))
+}
+
+pub fn batch_transfer(
+    balances: &mut impl BalancesStore,
+    locks: &LocksMap,
+    from: ActorId,
+    transfers: &[(ActorId, U256)],
+) -> Result<bool> {
+    let total = batch_total(from, transfers)?;
+
+    if total.is_zero() {
+        return Ok(false);
+    }
+
+    ensure_spendable(balances, locks, from, total)?;
+    ensure_receivable(balances, from, transfers)?;
+
+    for &(to, value) in transfers {
+        let Ok(_) = transfer(balances, locks, from, to, value) else {
+            unreachable!("Infallible since whole batch is checked above; qed");
+        };
+    }
+
+    Ok(true)
+}
+
+pub fn batch_transfer_from(
+    allowances: &mut impl AllowancesStore,
+    balances: &mut impl BalancesStore,
+    locks: &LocksMap,
+    spender: ActorId,
+    from: ActorId,
+    transfers: &[(ActorId, U256)],
+    now: u32,
+) -> Result<bool> {
+    if spender == from {
+        return batch_transfer(balances, locks, from, transfers);
+    }
+
+    let total = batch_total(from, transfers)?;
+
+    if total.is_zero() {
+        return Ok(false);
+    }
+
+    let (current, expires_at) = spendable_allowance(allowances, from, spender, now)?;
+
+    let new_allowance = current
+        .checked_sub(total)
+        .ok_or(Error::InsufficientAllowance)?;
+
+    let _res = batch_transfer(balances, locks, from, transfers)?;
+    debug_assert!(_res);
+
+    set_allowance(allowances, from, spender, new_allowance, expires_at);
+
+    Ok(true)
+}
+
+pub fn burn(
+    balances: &mut impl BalancesStore,
+    locks: &LocksMap,
+    total_supply: &mut U256,
     from: ActorId,
-    to: ActorId,
     value: U256,
 ) -> Result<bool> {
-    if from == to || value.is_zero() {
+    if value.is_zero() {
         return Ok(false);
     }
 
-    let new_from = balance_of(balances, from)
-        .checked_sub(value)
-        .ok_or(Error::InsufficientBalance)?;
+    ensure_spendable(balances, locks, from, value)?;
 
-    let new_to = balance_of(balances, to)
-        .checked_add(value)
-        .ok_or(Error::NumericOverflow)?;
+    let new_from = balance_of(balances, from) - value;
 
-    let Ok(non_zero_new_to) = new_to.try_into() else {
-        unreachable!("Infallible since fn is noop on zero value; qed");
+    let Some(new_total_supply) = total_supply.checked_sub(value) else {
+        unreachable!("Infallible since total supply covers any balance; qed");
     };
 
     if let Ok(non_zero_new_from) = new_from.try_into() {
@@ -93,190 +601,3392 @@ pub fn transfer(
         balances.remove(&from);
     }
 
-    balances.insert(to, non_zero_new_to);
+    *total_supply = new_total_supply;
 
     Ok(true)
 }
 
-pub fn transfer_from(
-    allowances: &mut AllowancesMap,
-    balances: &mut BalancesMap,
+#[allow(clippy::too_many_arguments)]
+pub fn burn_from(
+    allowances: &mut impl AllowancesStore,
+    balances: &mut impl BalancesStore,
+    locks: &LocksMap,
+    total_supply: &mut U256,
     spender: ActorId,
     from: ActorId,
-    to: ActorId,
     value: U256,
+    now: u32,
 ) -> Result<bool> {
     if spender == from {
-        return transfer(balances, from, to, value);
+        return burn(balances, locks, total_supply, from, value);
     }
 
-    if from == to || value.is_zero() {
+    if value.is_zero() {
         return Ok(false);
-    };
+    }
+
+    let (current, expires_at) = spendable_allowance(allowances, from, spender, now)?;
 
-    let new_allowance = allowance(allowances, from, spender)
+    let new_allowance = current
         .checked_sub(value)
         .ok_or(Error::InsufficientAllowance)?;
 
-    let _res = transfer(balances, from, to, value)?;
+    let _res = burn(balances, locks, total_supply, from, value)?;
     debug_assert!(_res);
 
-    let key = (from, spender);
-
-    if let Ok(non_zero_new_allowance) = new_allowance.try_into() {
-        allowances.insert(key, non_zero_new_allowance);
-    } else {
-        allowances.remove(&key);
-    }
+    set_allowance(allowances, from, spender, new_allowance, expires_at);
 
     Ok(true)
 }
 
-mod utils {
-    use super::*;
-
-    #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Decode, Encode, TypeInfo)]
-    pub struct NonZeroU256(U256);
+// Tokens for the schedule are moved from `from` to the `holder`, i.e. program itself.
+pub fn create_vesting(
+    vesting: &mut VestingMap,
+    balances: &mut impl BalancesStore,
+    locks: &LocksMap,
+    holder: ActorId,
+    from: ActorId,
+    schedule: VestingSchedule,
+) -> Result<bool> {
+    if schedule.duration == 0 || schedule.cliff > schedule.duration || !schedule.released.is_zero()
+    {
+        return Err(Error::InvalidSchedule);
+    }
 
-    impl TryFrom<U256> for NonZeroU256 {
-        type Error = TryFromU256Error;
+    if vesting.contains_key(&schedule.beneficiary) {
+        return Err(Error::VestingExists);
+    }
 
-        fn try_from(value: U256) -> Result<Self, Self::Error> {
-            (!value.is_zero())
-                .then_some(NonZeroU256(value))
-                .ok_or(TryFromU256Error(()))
-        }
+    if schedule.total.is_zero() {
+        return Ok(false);
     }
 
-    impl From<NonZeroU256> for U256 {
-        fn from(value: NonZeroU256) -> Self {
-            value.0
-        }
+    let _res = transfer(balances, locks, from, holder, schedule.total)?;
+    debug_assert!(_res);
+
+    vesting.insert(schedule.beneficiary, schedule);
+
+    Ok(true)
+}
+
+// Expired allowance is zero, so decreasing it fails with underflow.
+pub fn decrease_allowance(
+    allowances: &mut impl AllowancesStore,
+    owner: ActorId,
+    spender: ActorId,
+    delta: U256,
+    now: u32,
+) -> Result<bool> {
+    if owner == spender || delta.is_zero() {
+        return Ok(false);
     }
 
-    #[derive(Debug)]
-    pub struct TryFromU256Error(());
+    let (current, expires_at) =
+        active_allowance(allowances, owner, spender, now).unwrap_or_default();
+
+    let new_allowance = current
+        .checked_sub(delta)
+        .ok_or(Error::AllowanceUnderflow)?;
+
+    Ok(set_allowance(
+        allowances,
+        owner,
+        spender,
+        new_allowance,
+        expires_at,
+    ))
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use utils::*;
+// Returns previous delegate and votes changes, if delegate changed.
+pub fn delegate(
+    votes: &mut Votes,
+    balances: &impl BalancesStore,
+    delegator: ActorId,
+    delegatee: ActorId,
+    block: u32,
+) -> Option<(ActorId, Vec<VotesChange>)> {
+    let previous = delegates(votes, delegator);
 
-    // TODO (breathx): force macros to assert storage noop.
-    macro_rules! assert_ok {
-        ( $x:expr, $y: expr $(,)? ) => {{
-            assert_eq!($x.unwrap(), $y);
-        }};
+    if previous == delegatee {
+        return None;
     }
 
-    macro_rules! assert_err {
-        ( $x:expr, $y: expr $(,)? ) => {{
-            assert_eq!($x.err().expect("Ran into Ok value"), $y);
-        }};
+    if delegatee.is_zero() {
+        votes.delegates.remove(&delegator);
+    } else {
+        votes.delegates.insert(delegator, delegatee);
     }
 
-    #[test]
-    fn allowance() {
-        // Initializing thread logger.
-        let _ = env_logger::try_init();
+    let value = balance_of(balances, delegator);
 
-        // Creating map with one single approve from Alice to Bob.
-        let map = allowances_map([(alice(), bob(), U256::exp10(42))]);
+    Some((
+        previous,
+        move_delegate_votes(votes, previous, delegatee, value, block),
+    ))
+}
 
-        // # Test case #1.
-        // Approve is returned if exists.
-        {
-            assert!(map.contains_key(&(alice(), bob())));
-            assert_eq!(super::allowance(&map, alice(), bob()), U256::exp10(42));
-        }
+pub fn delegates(votes: &Votes, account: ActorId) -> ActorId {
+    votes
+        .delegates
+        .get(&account)
+        .copied()
+        .unwrap_or_else(ActorId::zero)
+}
 
-        // # Test case #2.
+pub fn domain_separator(name: &str, program_id: ActorId) -> [u8; 32] {
+    let encoded = (b"ERC20Permit", name, program_id).encode();
+
+    Blake2b256::digest(encoded).into()
+}
+
+pub fn ensure_not_frozen(blocklist: &BlocklistSet, account: ActorId) -> Result<()> {
+    (!is_frozen(blocklist, account))
+        .then_some(())
+        .ok_or(Error::AccountFrozen)
+}
+
+// Holders and approvals are expected in stable order, so chunks don't overlap.
+pub fn export_state(
+    balances: &impl BalancesStore,
+    allowances: &impl AllowancesStore,
+    holders: impl IntoIterator<Item = ActorId>,
+    approvals: impl IntoIterator<Item = (ActorId, ActorId)>,
+    meta: StateMeta,
+    offset: u32,
+    limit: u32,
+) -> StateChunk {
+    let limit = limit.min(MAX_PAGE_SIZE);
+
+    let mut holders = holders.into_iter().skip(offset as usize);
+    let mut approvals = approvals.into_iter().skip(offset as usize);
+
+    let balances_chunk = holders
+        .by_ref()
+        .take(limit as usize)
+        .filter_map(|account| balances.get(&account).map(|&value| (account, value.into())))
+        .collect();
+
+    let allowances_chunk = approvals
+        .by_ref()
+        .take(limit as usize)
+        .filter_map(|key| {
+            allowances
+                .get(&key)
+                .map(|&(value, expires_at)| (key.0, key.1, value.into(), expires_at))
+        })
+        .collect();
+
+    let next = (holders.next().is_some() || approvals.next().is_some())
+        .then(|| offset.saturating_add(limit));
+
+    StateChunk {
+        version: STATE_VERSION,
+        offset,
+        meta,
+        balances: balances_chunk,
+        allowances: allowances_chunk,
+        next,
+    }
+}
+
+pub fn freeze(blocklist: &mut BlocklistSet, account: ActorId) -> bool {
+    blocklist.insert(account)
+}
+
+pub fn get_past_votes(votes: &Votes, account: ActorId, block: u32, current: u32) -> Result<U256> {
+    if block >= current {
+        return Err(Error::FutureLookup);
+    }
+
+    let history = checkpoints_of(votes, account);
+
+    let idx = history.partition_point(|&(height, _)| height <= block);
+
+    Ok(idx
+        .checked_sub(1)
+        .map(|idx| history[idx].1)
+        .unwrap_or_default())
+}
+
+pub fn get_votes(votes: &Votes, account: ActorId) -> U256 {
+    checkpoints_of(votes, account)
+        .last()
+        .map(|&(_, value)| value)
+        .unwrap_or_default()
+}
+
+// Holders are expected in stable order, so pages don't shift between calls.
+pub fn holders(
+    balances: &impl BalancesStore,
+    accounts: impl IntoIterator<Item = ActorId>,
+    offset: u32,
+    limit: u32,
+) -> Vec<(ActorId, U256)> {
+    accounts
+        .into_iter()
+        .filter_map(|account| balances.get(&account).map(|&value| (account, value.into())))
+        .skip(offset as usize)
+        .take(limit.min(MAX_PAGE_SIZE) as usize)
+        .collect()
+}
+
+pub fn holders_count(balances: &impl BalancesStore) -> u32 {
+    balances.len().try_into().unwrap_or(u32::MAX)
+}
+
+// Chunks must come in order with the first one's meta, with accounts absent in current balances,
+// and sum of imported balances must reach chunks' total supply exactly with the last chunk.
+pub fn import_state(
+    balances: &mut impl BalancesStore,
+    allowances: &mut impl AllowancesStore,
+    total_supply: &mut U256,
+    cap: Option<U256>,
+    pending: &mut PendingImport,
+    chunk: StateChunk,
+) -> Result<bool> {
+    if chunk.version != STATE_VERSION {
+        return Err(Error::UnsupportedVersion);
+    }
+
+    let Some((offset, meta)) = pending.as_mut() else {
+        return Err(Error::ImportClosed);
+    };
+
+    if chunk.offset != *offset || chunk.meta != *meta {
+        return Err(Error::StateMismatch);
+    }
+
+    let expected = meta.total_supply;
+
+    if cap.is_some_and(|cap| expected > cap) {
+        return Err(Error::CapExceeded);
+    }
+
+    let mut accounts = HashSet::new();
+    let mut imported = *total_supply;
+
+    for &(account, value) in &chunk.balances {
+        if balances.contains_key(&account) || !accounts.insert(account) {
+            return Err(Error::StateMismatch);
+        }
+
+        imported = imported.checked_add(value).ok_or(Error::NumericOverflow)?;
+    }
+
+    if imported > expected || (chunk.next.is_none() && imported != expected) {
+        return Err(Error::StateMismatch);
+    }
+
+    for (account, value) in chunk.balances {
+        if let Ok(non_zero_value) = value.try_into() {
+            balances.insert(account, non_zero_value);
+        }
+    }
+
+    for (owner, spender, value, expires_at) in chunk.allowances {
+        set_allowance(allowances, owner, spender, value, expires_at);
+    }
+
+    *total_supply = imported;
+    match chunk.next {
+        Some(next) => *offset = next,
+        None => *pending = None,
+    }
+
+    Ok(pending.is_none())
+}
+
+// Expiry of active allowance is kept, while expired one is treated as zero.
+pub fn increase_allowance(
+    allowances: &mut impl AllowancesStore,
+    owner: ActorId,
+    spender: ActorId,
+    delta: U256,
+    now: u32,
+) -> Result<bool> {
+    if owner == spender || delta.is_zero() {
+        return Ok(false);
+    }
+
+    let (current, expires_at) =
+        active_allowance(allowances, owner, spender, now).unwrap_or_default();
+
+    let new_allowance = current.checked_add(delta).ok_or(Error::NumericOverflow)?;
+
+    Ok(set_allowance(
+        allowances,
+        owner,
+        spender,
+        new_allowance,
+        expires_at,
+    ))
+}
+
+pub fn is_frozen(blocklist: &BlocklistSet, account: ActorId) -> bool {
+    blocklist.contains(&account)
+}
+
+pub fn lock(
+    balances: &impl BalancesStore,
+    locks: &mut LocksMap,
+    account: ActorId,
+    value: U256,
+    until: u32,
+    now: u32,
+) -> Result<bool> {
+    if until <= now {
+        return Err(Error::InvalidLock);
+    }
+
+    if value.is_zero() {
+        return Ok(false);
+    }
+
+    ensure_spendable(balances, locks, account, value)?;
+    ensure_lockable(locks.get(&account).map(|locks| &locks.own), until)?;
+
+    add_lock(&mut locks.entry(account).or_default().own, value, until);
+
+    Ok(true)
+}
+
+pub fn locked_balance_of(locks: &LocksMap, account: ActorId, now: u32) -> U256 {
+    locks
+        .get(&account)
+        .into_iter()
+        .flat_map(Locks::iter)
+        .filter(|&&(until, _)| until > now)
+        .fold(U256::zero(), |acc, &(_, value)| acc.saturating_add(value))
+}
+
+pub fn mint(
+    balances: &mut impl BalancesStore,
+    total_supply: &mut U256,
+    cap: Option<U256>,
+    to: ActorId,
+    value: U256,
+) -> Result<bool> {
+    if value.is_zero() {
+        return Ok(false);
+    }
+
+    let new_total_supply = total_supply
+        .checked_add(value)
+        .ok_or(Error::NumericOverflow)?;
+
+    if cap.is_some_and(|cap| new_total_supply > cap) {
+        return Err(Error::CapExceeded);
+    }
+
+    let Some(new_to) = balance_of(balances, to).checked_add(value) else {
+        unreachable!("Infallible since total supply covers any balance; qed");
+    };
+
+    let Ok(non_zero_new_to) = new_to.try_into() else {
+        unreachable!("Infallible since fn is noop on zero value; qed");
+    };
+
+    balances.insert(to, non_zero_new_to);
+
+    *total_supply = new_total_supply;
+
+    Ok(true)
+}
+
+pub fn nonce_of(nonces: &NoncesMap, owner: ActorId) -> u64 {
+    nonces.get(&owner).cloned().unwrap_or_default()
+}
+
+pub fn permit(
+    allowances: &mut impl AllowancesStore,
+    nonces: &mut NoncesMap,
+    permit: &Permit,
+    signature: &Signature,
+    now: u64,
+) -> Result<bool> {
+    if permit.deadline < now {
+        return Err(Error::PermitExpired);
+    }
+
+    let nonce = nonce_of(nonces, permit.owner);
+
+    if permit.nonce != nonce {
+        return Err(Error::InvalidNonce);
+    }
+
+    if !verify(signature, &permit.encode(), permit.owner) {
+        return Err(Error::InvalidSignature);
+    }
+
+    let new_nonce = nonce.checked_add(1).ok_or(Error::NumericOverflow)?;
+
+    nonces.insert(permit.owner, new_nonce);
+
+    Ok(approve(
+        allowances,
+        permit.owner,
+        permit.spender,
+        permit.value,
+    ))
+}
+
+pub fn record_snapshot(
+    snapshots: &mut Snapshots,
+    balances: &impl BalancesStore,
+    total_supply: U256,
+    accounts: impl IntoIterator<Item = ActorId>,
+) {
+    let id = snapshots.current;
+
+    if id == 0 {
+        return;
+    }
+
+    for account in accounts {
+        let history = snapshots.balances.entry(account).or_default();
+
+        record(history, id, balance_of(balances, account));
+    }
+
+    record(&mut snapshots.total_supply, id, total_supply);
+}
+
+pub fn releasable_amount(vesting: &VestingMap, beneficiary: ActorId, now: u64) -> U256 {
+    vesting
+        .get(&beneficiary)
+        .map(|schedule| vested_of(schedule, now) - schedule.released)
+        .unwrap_or_default()
+}
+
+pub fn release(
+    vesting: &mut VestingMap,
+    balances: &mut impl BalancesStore,
+    locks: &LocksMap,
+    holder: ActorId,
+    beneficiary: ActorId,
+    now: u64,
+) -> Result<U256> {
+    let schedule = vesting
+        .get_mut(&beneficiary)
+        .ok_or(Error::VestingNotFound)?;
+
+    let value = vested_of(schedule, now) - schedule.released;
+
+    if value.is_zero() {
+        return Ok(value);
+    }
+
+    let _res = transfer(balances, locks, holder, beneficiary, value)?;
+    debug_assert!(_res);
+
+    schedule.released += value;
+
+    if schedule.released == schedule.total {
+        vesting.remove(&beneficiary);
+    }
+
+    Ok(value)
+}
+
+// Removes expired allowances too, returning spenders whose allowances were removed.
+pub fn revoke_all(
+    allowances: &mut impl AllowancesStore,
+    owner: ActorId,
+    spenders: impl IntoIterator<Item = ActorId>,
+) -> Vec<ActorId> {
+    spenders
+        .into_iter()
+        .filter(|&spender| allowances.remove(&(owner, spender)).is_some())
+        .collect()
+}
+
+// Unvested tokens are refunded to `to`, while vested ones stay releasable.
+pub fn revoke_vesting(
+    vesting: &mut VestingMap,
+    balances: &mut impl BalancesStore,
+    locks: &LocksMap,
+    holder: ActorId,
+    beneficiary: ActorId,
+    to: ActorId,
+    now: u64,
+) -> Result<U256> {
+    let schedule = vesting
+        .get_mut(&beneficiary)
+        .ok_or(Error::VestingNotFound)?;
+
+    if !schedule.revocable {
+        return Err(Error::NotRevocable);
+    }
+
+    let vested = vested_of(schedule, now);
+    let refund = schedule.total - vested;
+
+    let _res = transfer(balances, locks, holder, to, refund)?;
+    debug_assert_eq!(_res, !refund.is_zero() && holder != to);
+
+    // Schedule with zero duration is fully vested at any moment.
+    *schedule = VestingSchedule {
+        total: vested,
+        cliff: 0,
+        duration: 0,
+        revocable: false,
+        ..*schedule
+    };
+
+    if schedule.released == schedule.total {
+        vesting.remove(&beneficiary);
+    }
+
+    Ok(refund)
+}
+
+// Seized account's locks are dropped along with its balance.
+pub fn seize(
+    blocklist: &BlocklistSet,
+    balances: &mut impl BalancesStore,
+    locks: &mut LocksMap,
+    from: ActorId,
+    to: ActorId,
+) -> Result<U256> {
+    if !is_frozen(blocklist, from) {
+        return Err(Error::AccountNotFrozen);
+    }
+
+    ensure_not_frozen(blocklist, to)?;
+
+    let value = balance_of(balances, from);
+
+    // Checked before dropping locks, so failure leaves them in place.
+    balance_of(balances, to)
+        .checked_add(value)
+        .ok_or(Error::NumericOverflow)?;
+
+    locks.remove(&from);
+
+    let _res = transfer(balances, locks, from, to, value)?;
+    debug_assert_eq!(_res, !value.is_zero());
+
+    Ok(value)
+}
+
+pub fn snapshot(snapshots: &mut Snapshots) -> Result<u64> {
+    snapshots.current = snapshots
+        .current
+        .checked_add(1)
+        .ok_or(Error::NumericOverflow)?;
+
+    Ok(snapshots.current)
+}
+
+pub fn spenders_of(
+    allowances: &impl AllowancesStore,
+    owner: ActorId,
+    spenders: impl IntoIterator<Item = ActorId>,
+    now: u32,
+) -> Vec<ActorId> {
+    spenders
+        .into_iter()
+        .filter(|&spender| active_allowance(allowances, owner, spender, now).is_some())
+        .collect()
+}
+
+pub fn total_supply_at(snapshots: &Snapshots, total_supply: U256, id: u64) -> Result<U256> {
+    ensure_snapshot_exists(snapshots, id)?;
+
+    Ok(value_at(&snapshots.total_supply, id).unwrap_or(total_supply))
+}
+
+// Ties are broken by account, so result is deterministic.
+pub fn top_holders(balances: &impl BalancesStore, n: u32) -> Vec<(ActorId, U256)> {
+    let mut holders: Vec<_> = balances
+        .iter()
+        .map(|(&account, &value)| (account, U256::from(value)))
+        .collect();
+
+    holders.sort_unstable_by(|(a1, v1), (a2, v2)| v2.cmp(v1).then(a1.cmp(a2)));
+    holders.truncate(n.min(MAX_PAGE_SIZE) as usize);
+
+    holders
+}
+
+pub fn transfer(
+    balances: &mut impl BalancesStore,
+    locks: &LocksMap,
+    from: ActorId,
+    to: ActorId,
+    value: U256,
+) -> Result<bool> {
+    if from == to || value.is_zero() {
+        return Ok(false);
+    }
+
+    ensure_spendable(balances, locks, from, value)?;
+
+    let new_from = balance_of(balances, from) - value;
+
+    let new_to = balance_of(balances, to)
+        .checked_add(value)
+        .ok_or(Error::NumericOverflow)?;
+
+    let Ok(non_zero_new_to) = new_to.try_into() else {
+        unreachable!("Infallible since fn is noop on zero value; qed");
+    };
+
+    if let Ok(non_zero_new_from) = new_from.try_into() {
+        balances.insert(from, non_zero_new_from);
+    } else {
+        balances.remove(&from);
+    }
+
+    balances.insert(to, non_zero_new_to);
+
+    Ok(true)
+}
+
+// Transferred value stays locked on the receiver's side until `unlock_at`.
+pub fn transfer_locked(
+    balances: &mut impl BalancesStore,
+    locks: &mut LocksMap,
+    from: ActorId,
+    to: ActorId,
+    value: U256,
+    unlock_at: u32,
+    now: u32,
+) -> Result<bool> {
+    if unlock_at <= now {
+        return Err(Error::InvalidLock);
+    }
+
+    if from != to && !value.is_zero() {
+        ensure_lockable(locks.get(&to).map(|locks| &locks.received), unlock_at)?;
+    }
+
+    if !transfer(balances, locks, from, to, value)? {
+        return Ok(false);
+    }
+
+    add_lock(&mut locks.entry(to).or_default().received, value, unlock_at);
+
+    Ok(true)
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn transfer_from(
+    allowances: &mut impl AllowancesStore,
+    balances: &mut impl BalancesStore,
+    locks: &LocksMap,
+    spender: ActorId,
+    from: ActorId,
+    to: ActorId,
+    value: U256,
+    now: u32,
+) -> Result<bool> {
+    if spender == from {
+        return transfer(balances, locks, from, to, value);
+    }
+
+    if from == to || value.is_zero() {
+        return Ok(false);
+    };
+
+    let (current, expires_at) = spendable_allowance(allowances, from, spender, now)?;
+
+    let new_allowance = current
+        .checked_sub(value)
+        .ok_or(Error::InsufficientAllowance)?;
+
+    let _res = transfer(balances, locks, from, to, value)?;
+    debug_assert!(_res);
+
+    set_allowance(allowances, from, spender, new_allowance, expires_at);
+
+    Ok(true)
+}
+
+pub fn unfreeze(blocklist: &mut BlocklistSet, account: ActorId) -> bool {
+    blocklist.remove(&account)
+}
+
+// Locks are checked regardless of time, so expired ones must be dropped beforehand.
+pub fn unlock_expired(locks: &mut LocksMap, account: ActorId, now: u32) {
+    let Some(account_locks) = locks.get_mut(&account) else {
+        return;
+    };
+
+    account_locks.own.retain(|&(until, _)| until > now);
+    account_locks.received.retain(|&(until, _)| until > now);
+
+    if account_locks.is_empty() {
+        locks.remove(&account);
+    }
+}
+
+pub fn vested_amount(vesting: &VestingMap, beneficiary: ActorId, now: u64) -> U256 {
+    vesting
+        .get(&beneficiary)
+        .map(|schedule| vested_of(schedule, now))
+        .unwrap_or_default()
+}
+
+mod utils {
+    use super::*;
+    use blake2::{digest::consts::U32, Blake2b};
+
+    pub type Blake2b256 = Blake2b<U32>;
+
+    // Locks with the same expiry are merged, so value locked is bounded with balance.
+    pub fn add_lock(slots: &mut Vec<(u32, U256)>, value: U256, until: u32) {
+        match slots.iter_mut().find(|(at, _)| *at == until) {
+            Some((_, locked)) => {
+                let Some(new_locked) = locked.checked_add(value) else {
+                    unreachable!("Infallible since locked value is bounded with balance; qed");
+                };
+
+                *locked = new_locked;
+            }
+            None => slots.push((until, value)),
+        }
+    }
+
+    // Sum of values, which are actually moved by the batch (skipping noop ones).
+    pub fn batch_total(from: ActorId, transfers: &[(ActorId, U256)]) -> Result<U256> {
+        if transfers.len() > MAX_BATCH_SIZE {
+            return Err(Error::BatchTooLarge);
+        }
+
+        transfers
+            .iter()
+            .filter(|(to, _)| *to != from)
+            .try_fold(U256::zero(), |acc, (_, value)| {
+                acc.checked_add(*value).ok_or(Error::NumericOverflow)
+            })
+    }
+
+    // Lock either merges into existing one or takes a free slot.
+    pub fn ensure_lockable(slots: Option<&Vec<(u32, U256)>>, until: u32) -> Result<()> {
+        let Some(slots) = slots else {
+            return Ok(());
+        };
+
+        if slots.len() < MAX_LOCKS || slots.iter().any(|&(at, _)| at == until) {
+            Ok(())
+        } else {
+            Err(Error::TooManyLocks)
+        }
+    }
+
+    // Receivers' balances must not overflow, so batch could be applied entirely.
+    pub fn ensure_receivable(
+        balances: &impl BalancesStore,
+        from: ActorId,
+        transfers: &[(ActorId, U256)],
+    ) -> Result<()> {
+        let mut credited = BTreeMap::new();
+
+        for &(to, value) in transfers.iter().filter(|(to, _)| *to != from) {
+            let balance = credited
+                .entry(to)
+                .or_insert_with(|| balance_of(balances, to));
+
+            *balance = balance.checked_add(value).ok_or(Error::NumericOverflow)?;
+        }
+
+        Ok(())
+    }
+
+    // Allowance with its expiry, unless missing or expired.
+    pub fn active_allowance(
+        allowances: &impl AllowancesStore,
+        owner: ActorId,
+        spender: ActorId,
+        now: u32,
+    ) -> Option<(U256, Option<u32>)> {
+        let &(value, expires_at) = allowances.get(&(owner, spender))?;
+
+        if expires_at.is_some_and(|expires_at| expires_at <= now) {
+            return None;
+        }
+
+        Some((value.into(), expires_at))
+    }
+
+    // Same as `active_allowance`, but distinguishes expired one from missing.
+    pub fn spendable_allowance(
+        allowances: &impl AllowancesStore,
+        owner: ActorId,
+        spender: ActorId,
+        now: u32,
+    ) -> Result<(U256, Option<u32>)> {
+        match active_allowance(allowances, owner, spender, now) {
+            Some(allowance) => Ok(allowance),
+            None if allowances.contains_key(&(owner, spender)) => Err(Error::AllowanceExpired),
+            None => Ok(Default::default()),
+        }
+    }
+
+    pub fn set_allowance(
+        allowances: &mut impl AllowancesStore,
+        owner: ActorId,
+        spender: ActorId,
+        value: U256,
+        expires_at: Option<u32>,
+    ) -> bool {
+        let key = (owner, spender);
+
+        let Ok(non_zero_value) = value.try_into() else {
+            return allowances.remove(&key).is_some();
+        };
+
+        let prev = allowances.insert(key, (non_zero_value, expires_at));
+
+        prev.map(|v| v != (non_zero_value, expires_at))
+            .unwrap_or(true)
+    }
+
+    pub fn ensure_spendable(
+        balances: &impl BalancesStore,
+        locks: &LocksMap,
+        account: ActorId,
+        value: U256,
+    ) -> Result<()> {
+        let balance = balance_of(balances, account);
+
+        if balance < value {
+            return Err(Error::InsufficientBalance);
+        }
+
+        let locked = locks
+            .get(&account)
+            .into_iter()
+            .flat_map(Locks::iter)
+            .fold(U256::zero(), |acc, &(_, value)| acc.saturating_add(value));
+
+        if balance - value < locked {
+            return Err(Error::BalanceLocked);
+        }
+
+        Ok(())
+    }
+
+    pub fn checkpoints_of(votes: &Votes, account: ActorId) -> &[(u32, U256)] {
+        votes
+            .checkpoints
+            .get(&account)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    // Votes never exceed total supply, so arithmetic here can't fail.
+    pub fn move_delegate_votes(
+        votes: &mut Votes,
+        src: ActorId,
+        dst: ActorId,
+        value: U256,
+        block: u32,
+    ) -> Vec<VotesChange> {
+        let mut changes = Vec::new();
+
+        if src == dst || value.is_zero() {
+            return changes;
+        }
+
+        if !src.is_zero() {
+            let previous = get_votes(votes, src);
+            let Some(new) = previous.checked_sub(value) else {
+                unreachable!("Infallible since delegate's votes cover delegator's balance; qed");
+            };
+
+            write_checkpoint(votes.checkpoints.entry(src).or_default(), block, new);
+            changes.push((src, previous, new));
+        }
+
+        if !dst.is_zero() {
+            let previous = get_votes(votes, dst);
+            let Some(new) = previous.checked_add(value) else {
+                unreachable!("Infallible since votes are bounded by total supply; qed");
+            };
+
+            write_checkpoint(votes.checkpoints.entry(dst).or_default(), block, new);
+            changes.push((dst, previous, new));
+        }
+
+        changes
+    }
+
+    pub fn write_checkpoint(history: &mut Vec<(u32, U256)>, block: u32, value: U256) {
+        match history.last_mut() {
+            Some((height, last)) if *height == block => *last = value,
+            _ => history.push((block, value)),
+        }
+    }
+
+    pub fn ensure_snapshot_exists(snapshots: &Snapshots, id: u64) -> Result<()> {
+        (id != 0 && id <= snapshots.current)
+            .then_some(())
+            .ok_or(Error::SnapshotNotFound)
+    }
+
+    // Keeps only the first value per snapshot, which is the pre-change one.
+    pub fn record(history: &mut Vec<(u64, U256)>, id: u64, value: U256) {
+        if !matches!(history.last(), Some(&(last, _)) if last >= id) {
+            history.push((id, value))
+        }
+    }
+
+    // Value at snapshot `id` is the one recorded for the nearest snapshot since.
+    // `None` means no changes happened since `id`, so current value applies.
+    pub fn value_at(history: &[(u64, U256)], id: u64) -> Option<U256> {
+        let idx = history.partition_point(|&(recorded, _)| recorded < id);
+
+        history.get(idx).map(|&(_, value)| value)
+    }
+
+    pub fn vested_of(schedule: &VestingSchedule, now: u64) -> U256 {
+        let Some(elapsed) = now.checked_sub(schedule.start) else {
+            return U256::zero();
+        };
+
+        if elapsed >= schedule.duration {
+            return schedule.total;
+        }
+
+        if elapsed < schedule.cliff {
+            return U256::zero();
+        }
+
+        let vested = schedule.total.full_mul(elapsed.into()) / U512::from(schedule.duration);
+
+        vested
+            .try_into()
+            .expect("Infallible since `elapsed` is less than `duration`")
+    }
+
+    pub fn verify(signature: &Signature, message: &[u8], signer: ActorId) -> bool {
+        let public_key: [u8; 32] = signer.into();
+
+        match signature {
+            Signature::Ed25519(signature) => {
+                let Ok(public_key) = ed25519_dalek::VerifyingKey::from_bytes(&public_key) else {
+                    return false;
+                };
+
+                let signature = ed25519_dalek::Signature::from_bytes(signature);
+
+                public_key.verify_strict(message, &signature).is_ok()
+            }
+            Signature::Sr25519(signature) => {
+                let Ok(public_key) = schnorrkel::PublicKey::from_bytes(&public_key) else {
+                    return false;
+                };
+
+                let Ok(signature) = schnorrkel::Signature::from_bytes(signature) else {
+                    return false;
+                };
+
+                public_key
+                    .verify_simple(SR25519_SIGNING_CONTEXT, message, &signature)
+                    .is_ok()
+            }
+        }
+    }
+
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Decode, Encode, TypeInfo)]
+    pub struct NonZeroU256(U256);
+
+    impl TryFrom<U256> for NonZeroU256 {
+        type Error = TryFromU256Error;
+
+        fn try_from(value: U256) -> Result<Self, Self::Error> {
+            (!value.is_zero())
+                .then_some(NonZeroU256(value))
+                .ok_or(TryFromU256Error(()))
+        }
+    }
+
+    impl From<NonZeroU256> for U256 {
+        fn from(value: NonZeroU256) -> Self {
+            value.0
+        }
+    }
+
+    #[derive(Debug)]
+    pub struct TryFromU256Error(());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use utils::*;
+
+    // TODO (breathx): force macros to assert storage noop.
+    macro_rules! assert_ok {
+        ( $x:expr, $y: expr $(,)? ) => {{
+            assert_eq!($x.unwrap(), $y);
+        }};
+    }
+
+    macro_rules! assert_err {
+        ( $x:expr, $y: expr $(,)? ) => {{
+            assert_eq!($x.err().expect("Ran into Ok value"), $y);
+        }};
+    }
+
+    #[test]
+    fn allowance() {
+        // Initializing thread logger.
+        let _ = env_logger::try_init();
+
+        // Creating map with one single approve from Alice to Bob.
+        let map = allowances_map([(alice(), bob(), U256::exp10(42))]);
+
+        // # Test case #1.
+        // Approve is returned if exists.
+        {
+            assert!(map.contains_key(&(alice(), bob())));
+            assert_eq!(super::allowance(&map, alice(), bob(), 0), U256::exp10(42));
+        }
+
+        // # Test case #2.
+        // U256::zero() is returned if not exists.
+        {
+            assert!(!map.contains_key(&(bob(), alice())));
+            assert!(super::allowance(&map, bob(), alice(), 0).is_zero());
+        }
+    }
+
+    #[test]
+    fn allowances_of() {
+        // Initializing thread logger.
+        let _ = env_logger::try_init();
+
+        // Creating map with Alice's approves to Bob, Charlie and Dave.
+        let mut map = allowances_map([
+            (alice(), bob(), U256::exp10(42)),
+            (alice(), charlie(), U256::exp10(24)),
+            (alice(), dave(), U256::exp10(12)),
+        ]);
+
+        // Spenders as indexed by storage.
+        let spenders = [bob(), charlie(), dave()];
+
+        // # Test case #1.
+        // Allowances are paginated in spenders order.
+        {
+            assert_eq!(
+                super::allowances_of(&map, alice(), spenders, 0, 2, 0),
+                vec![(bob(), U256::exp10(42)), (charlie(), U256::exp10(24))]
+            );
+            assert_eq!(
+                super::allowances_of(&map, alice(), spenders, 2, 2, 0),
+                vec![(dave(), U256::exp10(12))]
+            );
+            assert!(super::allowances_of(&map, alice(), spenders, 3, 2, 0).is_empty());
+            assert!(super::allowances_of(&map, bob(), spenders, 0, 2, 0).is_empty());
+        }
+
+        // # Test case #2.
+        // Expired allowances are skipped.
+        {
+            assert_ok!(
+                super::approve_with_expiry(&mut map, alice(), charlie(), U256::one(), 10, 0),
+                true
+            );
+
+            assert_eq!(
+                super::allowances_of(&map, alice(), spenders, 0, 2, 10),
+                vec![(bob(), U256::exp10(42)), (dave(), U256::exp10(12))]
+            );
+            assert_eq!(
+                super::spenders_of(&map, alice(), spenders, 10),
+                vec![bob(), dave()]
+            );
+            assert_eq!(super::spenders_of(&map, alice(), spenders, 9), spenders);
+        }
+    }
+
+    #[test]
+    fn approve() {
+        // Initializing thread logger.
+        let _ = env_logger::try_init();
+
+        // Creating empty map.
+        let mut map = allowances_map([]);
+        assert!(map.is_empty());
+
+        // # Test case #1.
+        // Allowance from Alice to Bob doesn't exist and created.
+        {
+            assert!(super::approve(&mut map, alice(), bob(), U256::exp10(42)));
+            assert_eq!(super::allowance(&map, alice(), bob(), 0), U256::exp10(42));
+        }
+
+        // # Test case #2.
+        // Allowance from Alice to Bob exist and changed.
+        {
+            assert!(super::approve(&mut map, alice(), bob(), U256::exp10(24)));
+            assert_eq!(super::allowance(&map, alice(), bob(), 0), U256::exp10(24));
+        }
+
+        // # Test case #3.
+        // Allowance from Alice to Bob exists and not changed.
+        {
+            assert!(!super::approve(&mut map, alice(), bob(), U256::exp10(24)));
+            assert_eq!(super::allowance(&map, alice(), bob(), 0), U256::exp10(24));
+        }
+
+        // # Test case #4.
+        // Allowance from Alice to Bob exists and removed.
+        {
+            assert!(super::approve(&mut map, alice(), bob(), U256::zero()));
+            assert!(super::allowance(&map, alice(), bob(), 0).is_zero());
+        }
+
+        // # Test case #5.
+        // Allowance from Alice to Bob doesn't exists and not created.
+        {
+            assert!(!super::approve(&mut map, alice(), bob(), U256::zero()));
+            assert!(super::allowance(&map, alice(), bob(), 0).is_zero());
+        }
+
+        // # Test case #6.
+        // Allowance is always noop on owner == spender.
+        {
+            assert!(!super::approve(&mut map, alice(), alice(), U256::exp10(42)));
+            assert!(super::allowance(&map, alice(), alice(), 0).is_zero());
+
+            assert!(!super::approve(&mut map, alice(), alice(), U256::exp10(24)));
+            assert!(super::allowance(&map, alice(), alice(), 0).is_zero());
+
+            assert!(!super::approve(&mut map, alice(), alice(), U256::zero()));
+            assert!(super::allowance(&map, alice(), alice(), 0).is_zero());
+        }
+    }
+
+    #[test]
+    fn approve_with_expiry() {
+        // Initializing thread logger.
+        let _ = env_logger::try_init();
+
+        // Creating empty allowances map.
+        let mut amap = allowances_map([]);
+
+        // Creating map with medium balance belonged to Alice.
+        let mut bmap = balances_map([(alice(), U256::exp10(42))]);
+
+        // # Test case #1.
+        // Expiry must be in future.
+        {
+            assert_err!(
+                super::approve_with_expiry(&mut amap, alice(), bob(), U256::exp10(41), 10, 10),
+                Error::AllowanceExpired
+            );
+            assert!(amap.is_empty());
+        }
+
+        // # Test case #2.
+        // Allowance is active until expiry block.
+        {
+            assert_ok!(
+                super::approve_with_expiry(&mut amap, alice(), bob(), U256::exp10(41), 20, 10),
+                true
+            );
+
+            assert_eq!(super::allowance(&amap, alice(), bob(), 19), U256::exp10(41));
+            assert!(super::allowance(&amap, alice(), bob(), 20).is_zero());
+        }
+
+        // # Test case #3.
+        // Spending active allowance keeps its expiry.
+        {
+            assert_ok!(
+                super::transfer_from(
+                    &mut amap,
+                    &mut bmap,
+                    &LocksMap::new(),
+                    bob(),
+                    alice(),
+                    charlie(),
+                    U256::exp10(40),
+                    15
+                ),
+                true
+            );
+
+            assert_eq!(
+                amap[&(alice(), bob())],
+                (non_zero(U256::exp10(41) - U256::exp10(40)), Some(20))
+            );
+        }
+
+        // # Test case #4.
+        // Expired allowance can't be spent.
+        {
+            assert_err!(
+                super::transfer_from(
+                    &mut amap,
+                    &mut bmap,
+                    &LocksMap::new(),
+                    bob(),
+                    alice(),
+                    charlie(),
+                    U256::one(),
+                    20
+                ),
+                Error::AllowanceExpired
+            );
+            assert_err!(
+                super::burn_from(
+                    &mut amap,
+                    &mut bmap,
+                    &LocksMap::new(),
+                    &mut U256::exp10(42),
+                    bob(),
+                    alice(),
+                    U256::one(),
+                    20
+                ),
+                Error::AllowanceExpired
+            );
+
+            assert_eq!(super::balance_of(&bmap, charlie()), U256::exp10(40));
+        }
+
+        // # Test case #5.
+        // Increasing expired allowance starts from zero with no expiry.
+        {
+            assert_ok!(
+                super::increase_allowance(&mut amap, alice(), bob(), U256::one(), 20),
+                true
+            );
+
+            assert_eq!(amap[&(alice(), bob())], (non_zero(U256::one()), None));
+        }
+
+        // # Test case #6.
+        // Plain approval overrides expiry.
+        {
+            assert_ok!(
+                super::approve_with_expiry(&mut amap, alice(), bob(), U256::one(), 30, 20),
+                true
+            );
+            assert!(super::approve(&mut amap, alice(), bob(), U256::one()));
+
+            assert_eq!(amap[&(alice(), bob())], (non_zero(U256::one()), None));
+        }
+    }
+
+    #[test]
+    fn balance_of() {
+        // Initializing thread logger.
+        let _ = env_logger::try_init();
+
+        // Creating map with one single balance belonged to Alice.
+        let map = balances_map([(alice(), U256::exp10(42))]);
+
+        // # Test case #1.
+        // Balance is returned if exists.
+        {
+            assert!(map.contains_key(&alice()));
+            assert_eq!(super::balance_of(&map, alice()), U256::exp10(42));
+        }
+
+        // # Test case #2.
         // U256::zero() is returned if not exists.
         {
-            assert!(!map.contains_key(&(bob(), alice())));
-            assert!(super::allowance(&map, bob(), alice()).is_zero());
+            assert!(!map.contains_key(&bob()));
+            assert!(super::balance_of(&map, bob()).is_zero());
+        }
+    }
+
+    // Since this uses [`super::transfer`] in underlying impl, it needs only
+    // check batch specific logic.
+    #[test]
+    fn batch_transfer() {
+        // Initializing thread logger.
+        let _ = env_logger::try_init();
+
+        // Creating map with medium balance belonged to Alice.
+        let mut map = balances_map([(alice(), U256::exp10(42))]);
+
+        // # Test case #1.
+        // Batch exceeding max size is rejected.
+        {
+            let transfers = vec![(bob(), U256::one()); MAX_BATCH_SIZE + 1];
+
+            assert_err!(
+                super::batch_transfer(&mut map, &LocksMap::new(), alice(), &transfers),
+                Error::BatchTooLarge
+            );
+            assert_eq!(super::balance_of(&map, alice()), U256::exp10(42));
+        }
+
+        // # Test case #2.
+        // Batch exceeding balance in total is rejected with no changes applied.
+        {
+            let transfers = [(bob(), U256::exp10(42)), (charlie(), U256::one())];
+
+            assert_err!(
+                super::batch_transfer(&mut map, &LocksMap::new(), alice(), &transfers),
+                Error::InsufficientBalance
+            );
+            assert_eq!(super::balance_of(&map, alice()), U256::exp10(42));
+            assert!(super::balance_of(&map, bob()).is_zero());
+        }
+
+        // # Test case #3.
+        // Batch overflowing in total is rejected.
+        {
+            let transfers = [(bob(), U256::MAX), (charlie(), U256::one())];
+
+            assert_err!(
+                super::batch_transfer(&mut map, &LocksMap::new(), alice(), &transfers),
+                Error::NumericOverflow
+            );
+            assert_eq!(super::balance_of(&map, alice()), U256::exp10(42));
+        }
+
+        // # Test case #4.
+        // Batch is applied, skipping self and zero transfers.
+        {
+            let transfers = [
+                (bob(), U256::exp10(20)),
+                (alice(), U256::exp10(42)),
+                (charlie(), U256::zero()),
+                (bob(), U256::exp10(20)),
+                (dave(), U256::exp10(20)),
+            ];
+
+            assert_ok!(
+                super::batch_transfer(&mut map, &LocksMap::new(), alice(), &transfers),
+                true
+            );
+            assert_eq!(
+                super::balance_of(&map, alice()),
+                U256::exp10(42) - U256::exp10(20).saturating_mul(3.into())
+            );
+            assert_eq!(
+                super::balance_of(&map, bob()),
+                U256::exp10(20).saturating_mul(2.into())
+            );
+            assert!(super::balance_of(&map, charlie()).is_zero());
+            assert_eq!(super::balance_of(&map, dave()), U256::exp10(20));
+        }
+
+        // # Test case #5.
+        // Batch is noop when empty or consists of noop transfers.
+        {
+            assert_ok!(
+                super::batch_transfer(&mut map, &LocksMap::new(), alice(), &[]),
+                false
+            );
+            assert_ok!(
+                super::batch_transfer(
+                    &mut map,
+                    &LocksMap::new(),
+                    alice(),
+                    &[(charlie(), U256::zero())]
+                ),
+                false
+            );
+            assert!(super::balance_of(&map, charlie()).is_zero());
+        }
+
+        // # Test case #6.
+        // Batch overflowing the last receiver is rejected with no changes applied.
+        {
+            map.insert(dave(), U256::MAX.try_into().unwrap());
+
+            let initial = map.clone();
+
+            let transfers = [
+                (bob(), U256::one()),
+                (charlie(), U256::one()),
+                (dave(), U256::one()),
+            ];
+
+            assert_err!(
+                super::batch_transfer(&mut map, &LocksMap::new(), alice(), &transfers),
+                Error::NumericOverflow
+            );
+            assert_eq!(map, initial);
+        }
+    }
+
+    #[test]
+    fn batch_transfer_from() {
+        // Initializing thread logger.
+        let _ = env_logger::try_init();
+
+        // Creating empty allowances map.
+        let mut amap = allowances_map([]);
+
+        // Creating balances map with medium balance belonged to Alice.
+        let mut bmap = balances_map([(alice(), U256::exp10(42))]);
+
+        let transfers = [(charlie(), U256::exp10(20)), (dave(), U256::exp10(20))];
+
+        // # Test case #1.
+        // Bob tries to perform batch from Alice with insufficient approval.
+        {
+            assert!(super::approve(&mut amap, alice(), bob(), U256::exp10(20)));
+
+            assert_err!(
+                super::batch_transfer_from(
+                    &mut amap,
+                    &mut bmap,
+                    &LocksMap::new(),
+                    bob(),
+                    alice(),
+                    &transfers,
+                    0
+                ),
+                Error::InsufficientAllowance
+            );
+            assert_eq!(super::balance_of(&bmap, alice()), U256::exp10(42));
+            assert_eq!(super::allowance(&amap, alice(), bob(), 0), U256::exp10(20));
+        }
+
+        // # Test case #2.
+        // Bob performs batch from Alice and allowance is removed.
+        {
+            assert!(super::approve(
+                &mut amap,
+                alice(),
+                bob(),
+                U256::exp10(20).saturating_mul(2.into())
+            ));
+
+            assert_ok!(
+                super::batch_transfer_from(
+                    &mut amap,
+                    &mut bmap,
+                    &LocksMap::new(),
+                    bob(),
+                    alice(),
+                    &transfers,
+                    0
+                ),
+                true
+            );
+            assert_eq!(super::balance_of(&bmap, charlie()), U256::exp10(20));
+            assert_eq!(super::balance_of(&bmap, dave()), U256::exp10(20));
+            assert!(super::allowance(&amap, alice(), bob(), 0).is_zero());
+        }
+
+        // # Test case #3.
+        // Alice doesn't need approve to perform batch from self.
+        {
+            assert_ok!(
+                super::batch_transfer_from(
+                    &mut amap,
+                    &mut bmap,
+                    &LocksMap::new(),
+                    alice(),
+                    alice(),
+                    &transfers,
+                    0
+                ),
+                true
+            );
+            assert_eq!(
+                super::balance_of(&bmap, charlie()),
+                U256::exp10(20).saturating_mul(2.into())
+            );
+        }
+
+        // # Test case #4.
+        // Batch overflowing the last receiver leaves balances and allowance untouched.
+        {
+            bmap.insert(dave(), U256::MAX.try_into().unwrap());
+
+            assert!(super::approve(&mut amap, alice(), bob(), U256::exp10(42)));
+
+            let initial = bmap.clone();
+
+            assert_err!(
+                super::batch_transfer_from(
+                    &mut amap,
+                    &mut bmap,
+                    &LocksMap::new(),
+                    bob(),
+                    alice(),
+                    &transfers,
+                    0
+                ),
+                Error::NumericOverflow
+            );
+            assert_eq!(bmap, initial);
+            assert_eq!(super::allowance(&amap, alice(), bob(), 0), U256::exp10(42));
+        }
+    }
+
+    #[test]
+    fn burn() {
+        // Initializing thread logger.
+        let _ = env_logger::try_init();
+
+        // Creating map with medium balances belonged to Alice and Bob.
+        let mut map = balances_map([(alice(), U256::exp10(42)), (bob(), U256::exp10(42))]);
+        let mut total_supply = U256::exp10(42).saturating_mul(2.into());
+
+        // # Test case #1.
+        // Burning from Charlie, when Charlie has no balance.
+        {
+            assert_err!(
+                super::burn(
+                    &mut map,
+                    &LocksMap::new(),
+                    &mut total_supply,
+                    charlie(),
+                    U256::exp10(20)
+                ),
+                Error::InsufficientBalance
+            );
+
+            assert!(super::balance_of(&map, charlie()).is_zero());
+            assert_eq!(total_supply, total_supply_of(&map));
+        }
+
+        // # Test case #2.
+        // Burning from Alice more than her balance.
+        {
+            assert_err!(
+                super::burn(
+                    &mut map,
+                    &LocksMap::new(),
+                    &mut total_supply,
+                    alice(),
+                    U256::exp10(43)
+                ),
+                Error::InsufficientBalance
+            );
+
+            assert_eq!(super::balance_of(&map, alice()), U256::exp10(42));
+            assert_eq!(total_supply, total_supply_of(&map));
+        }
+
+        // # Test case #3.
+        // Burning from Alice part of her balance.
+        {
+            assert_ok!(
+                super::burn(
+                    &mut map,
+                    &LocksMap::new(),
+                    &mut total_supply,
+                    alice(),
+                    U256::exp10(20)
+                ),
+                true
+            );
+
+            assert_eq!(
+                super::balance_of(&map, alice()),
+                U256::exp10(42) - U256::exp10(20)
+            );
+            assert_eq!(total_supply, total_supply_of(&map));
+        }
+
+        // # Test case #4.
+        // Burning from Bob whole his balance, so Bob's account is removed.
+        {
+            assert_ok!(
+                super::burn(
+                    &mut map,
+                    &LocksMap::new(),
+                    &mut total_supply,
+                    bob(),
+                    U256::exp10(42)
+                ),
+                true
+            );
+
+            assert!(!map.contains_key(&bob()));
+            assert_eq!(total_supply, total_supply_of(&map));
+        }
+
+        // # Test case #5.
+        // Burn is always noop when value is zero.
+        {
+            assert_ok!(
+                super::burn(
+                    &mut map,
+                    &LocksMap::new(),
+                    &mut total_supply,
+                    alice(),
+                    U256::zero()
+                ),
+                false
+            );
+            assert_ok!(
+                super::burn(
+                    &mut map,
+                    &LocksMap::new(),
+                    &mut total_supply,
+                    bob(),
+                    U256::zero()
+                ),
+                false
+            );
+
+            assert_eq!(
+                super::balance_of(&map, alice()),
+                U256::exp10(42) - U256::exp10(20)
+            );
+            assert!(super::balance_of(&map, bob()).is_zero());
+            assert_eq!(total_supply, total_supply_of(&map));
+        }
+    }
+
+    // Since this uses [`super::burn`] in underlying impl, it needs only
+    // check approval specific logic and few burn's happy cases.
+    #[test]
+    fn burn_from() {
+        // Initializing thread logger.
+        let _ = env_logger::try_init();
+
+        // Creating empty allowances map.
+        let mut amap = allowances_map([]);
+
+        // Creating balances map with two equal balances belonged to Alice and Bob.
+        let mut bmap = balances_map([(alice(), U256::exp10(42)), (bob(), U256::exp10(42))]);
+        let mut total_supply = total_supply_of(&bmap);
+
+        // # Test case #1.
+        // Alice doesn't need approve to burn from self.
+        {
+            assert_ok!(
+                super::burn_from(
+                    &mut amap,
+                    &mut bmap,
+                    &LocksMap::new(),
+                    &mut total_supply,
+                    alice(),
+                    alice(),
+                    U256::exp10(20),
+                    0
+                ),
+                true
+            );
+
+            assert_eq!(
+                super::balance_of(&bmap, alice()),
+                U256::exp10(42) - U256::exp10(20)
+            );
+            assert_eq!(total_supply, total_supply_of(&bmap));
+        }
+
+        // # Test case #2.
+        // Charlie tries to burn from Bob with no approval exists.
+        {
+            assert_err!(
+                super::burn_from(
+                    &mut amap,
+                    &mut bmap,
+                    &LocksMap::new(),
+                    &mut total_supply,
+                    charlie(),
+                    bob(),
+                    U256::exp10(20),
+                    0
+                ),
+                Error::InsufficientAllowance
+            );
+
+            assert_eq!(super::balance_of(&bmap, bob()), U256::exp10(42));
+            assert_eq!(total_supply, total_supply_of(&bmap));
+        }
+
+        // # Test case #3.
+        // Charlie tries to burn from Bob with insufficient approval.
+        {
+            assert!(super::approve(&mut amap, bob(), charlie(), U256::exp10(19)));
+
+            assert_err!(
+                super::burn_from(
+                    &mut amap,
+                    &mut bmap,
+                    &LocksMap::new(),
+                    &mut total_supply,
+                    charlie(),
+                    bob(),
+                    U256::exp10(20),
+                    0
+                ),
+                Error::InsufficientAllowance
+            );
+
+            assert_eq!(super::balance_of(&bmap, bob()), U256::exp10(42));
+            assert_eq!(
+                super::allowance(&amap, bob(), charlie(), 0),
+                U256::exp10(19)
+            );
+            assert_eq!(total_supply, total_supply_of(&bmap));
+        }
+
+        // # Test case #4.
+        // Charlie tries to burn from Bob with insufficient balance.
+        {
+            assert!(super::approve(&mut amap, bob(), charlie(), U256::MAX));
+
+            assert_err!(
+                super::burn_from(
+                    &mut amap,
+                    &mut bmap,
+                    &LocksMap::new(),
+                    &mut total_supply,
+                    charlie(),
+                    bob(),
+                    U256::exp10(43),
+                    0
+                ),
+                Error::InsufficientBalance
+            );
+
+            assert_eq!(super::balance_of(&bmap, bob()), U256::exp10(42));
+            assert_eq!(super::allowance(&amap, bob(), charlie(), 0), U256::MAX);
+            assert_eq!(total_supply, total_supply_of(&bmap));
+        }
+
+        // # Test case #5.
+        // Charlie burns from Bob and allowance is changed.
+        {
+            assert_ok!(
+                super::burn_from(
+                    &mut amap,
+                    &mut bmap,
+                    &LocksMap::new(),
+                    &mut total_supply,
+                    charlie(),
+                    bob(),
+                    U256::exp10(20),
+                    0
+                ),
+                true
+            );
+
+            assert_eq!(
+                super::balance_of(&bmap, bob()),
+                U256::exp10(42) - U256::exp10(20)
+            );
+            assert_eq!(
+                super::allowance(&amap, bob(), charlie(), 0),
+                U256::MAX - U256::exp10(20)
+            );
+            assert_eq!(total_supply, total_supply_of(&bmap));
+        }
+
+        // # Test case #6.
+        // Dave burns from Alice whole her balance and allowance is removed.
+        {
+            let balance = super::balance_of(&bmap, alice());
+
+            assert!(super::approve(&mut amap, alice(), dave(), balance));
+
+            assert_ok!(
+                super::burn_from(
+                    &mut amap,
+                    &mut bmap,
+                    &LocksMap::new(),
+                    &mut total_supply,
+                    dave(),
+                    alice(),
+                    balance,
+                    0
+                ),
+                true
+            );
+
+            assert!(super::balance_of(&bmap, alice()).is_zero());
+            assert!(super::allowance(&amap, alice(), dave(), 0).is_zero());
+            assert_eq!(total_supply, total_supply_of(&bmap));
+        }
+
+        // # Test case #7.
+        // Burn from is always noop when value is zero.
+        {
+            assert_ok!(
+                super::burn_from(
+                    &mut amap,
+                    &mut bmap,
+                    &LocksMap::new(),
+                    &mut total_supply,
+                    charlie(),
+                    bob(),
+                    U256::zero(),
+                    0
+                ),
+                false
+            );
+
+            assert_eq!(
+                super::allowance(&amap, bob(), charlie(), 0),
+                U256::MAX - U256::exp10(20)
+            );
+            assert_eq!(total_supply, total_supply_of(&bmap));
+        }
+    }
+
+    #[test]
+    fn create_vesting() {
+        // Initializing thread logger.
+        let _ = env_logger::try_init();
+
+        // Creating map with medium balance belonged to Alice.
+        let mut map = balances_map([(alice(), U256::exp10(42))]);
+
+        let mut vesting = VestingMap::new();
+
+        // # Test case #1.
+        // Schedules with zero duration or cliff after the end are invalid.
+        {
+            for (cliff, duration) in [(0, 0), (101, 100)] {
+                let schedule = VestingSchedule {
+                    cliff,
+                    duration,
+                    ..schedule_of(bob(), U256::exp10(41))
+                };
+
+                assert_err!(
+                    super::create_vesting(
+                        &mut vesting,
+                        &mut map,
+                        &LocksMap::new(),
+                        dave(),
+                        alice(),
+                        schedule
+                    ),
+                    Error::InvalidSchedule
+                );
+            }
+
+            assert!(vesting.is_empty());
+        }
+
+        // # Test case #2.
+        // Alice can't fund schedule over her balance.
+        {
+            let schedule = schedule_of(bob(), U256::exp10(43));
+
+            assert_err!(
+                super::create_vesting(
+                    &mut vesting,
+                    &mut map,
+                    &LocksMap::new(),
+                    dave(),
+                    alice(),
+                    schedule
+                ),
+                Error::InsufficientBalance
+            );
+
+            assert!(vesting.is_empty());
+        }
+
+        // # Test case #3.
+        // Alice creates schedule for Bob, so tokens are held by program.
+        {
+            let schedule = schedule_of(bob(), U256::exp10(41));
+
+            assert_ok!(
+                super::create_vesting(
+                    &mut vesting,
+                    &mut map,
+                    &LocksMap::new(),
+                    dave(),
+                    alice(),
+                    schedule
+                ),
+                true
+            );
+
+            assert_eq!(vesting[&bob()], schedule);
+            assert_eq!(super::balance_of(&map, dave()), U256::exp10(41));
+            assert!(super::balance_of(&map, bob()).is_zero());
+        }
+
+        // # Test case #4.
+        // Bob can't have two schedules.
+        {
+            let schedule = schedule_of(bob(), U256::exp10(41));
+
+            assert_err!(
+                super::create_vesting(
+                    &mut vesting,
+                    &mut map,
+                    &LocksMap::new(),
+                    dave(),
+                    alice(),
+                    schedule
+                ),
+                Error::VestingExists
+            );
+
+            assert_eq!(super::balance_of(&map, dave()), U256::exp10(41));
+        }
+    }
+
+    #[test]
+    fn decrease_allowance() {
+        // Initializing thread logger.
+        let _ = env_logger::try_init();
+
+        // Creating map with one single approve from Alice to Bob.
+        let mut map = allowances_map([(alice(), bob(), U256::exp10(42))]);
+
+        // # Test case #1.
+        // Allowance from Alice to Bob is decreased.
+        {
+            assert_ok!(
+                super::decrease_allowance(&mut map, alice(), bob(), U256::exp10(20), 0),
+                true
+            );
+            assert_eq!(
+                super::allowance(&map, alice(), bob(), 0),
+                U256::exp10(42) - U256::exp10(20)
+            );
+        }
+
+        // # Test case #2.
+        // Allowance from Alice to Bob can't be decreased below zero.
+        {
+            assert_err!(
+                super::decrease_allowance(&mut map, alice(), bob(), U256::exp10(42), 0),
+                Error::AllowanceUnderflow
+            );
+            assert_eq!(
+                super::allowance(&map, alice(), bob(), 0),
+                U256::exp10(42) - U256::exp10(20)
+            );
+
+            assert_err!(
+                super::decrease_allowance(&mut map, bob(), alice(), U256::one(), 0),
+                Error::AllowanceUnderflow
+            );
+        }
+
+        // # Test case #3.
+        // Allowance from Alice to Bob is decreased to zero and removed.
+        {
+            assert_ok!(
+                super::decrease_allowance(
+                    &mut map,
+                    alice(),
+                    bob(),
+                    U256::exp10(42) - U256::exp10(20),
+                    0
+                ),
+                true
+            );
+            assert!(!map.contains_key(&(alice(), bob())));
+        }
+
+        // # Test case #4.
+        // Decrease is noop on zero delta or owner == spender.
+        {
+            assert_ok!(
+                super::decrease_allowance(&mut map, alice(), bob(), U256::zero(), 0),
+                false
+            );
+            assert_ok!(
+                super::decrease_allowance(&mut map, alice(), alice(), U256::exp10(42), 0),
+                false
+            );
+        }
+    }
+
+    #[test]
+    fn delegate() {
+        // Initializing thread logger.
+        let _ = env_logger::try_init();
+
+        // Creating map with medium balances belonged to Alice and Bob.
+        let map = balances_map([(alice(), U256::exp10(42)), (bob(), U256::exp10(41))]);
+
+        let mut votes = Votes::default();
+
+        // # Test case #1.
+        // Alice delegates to herself.
+        {
+            assert_eq!(
+                super::delegate(&mut votes, &map, alice(), alice(), 1),
+                Some((
+                    ActorId::zero(),
+                    vec![(alice(), U256::zero(), U256::exp10(42))]
+                ))
+            );
+
+            assert_eq!(super::delegates(&votes, alice()), alice());
+            assert_eq!(super::get_votes(&votes, alice()), U256::exp10(42));
+        }
+
+        // # Test case #2.
+        // Delegating to the same delegate is noop.
+        {
+            assert_eq!(super::delegate(&mut votes, &map, alice(), alice(), 2), None);
+            assert_eq!(super::get_votes(&votes, alice()), U256::exp10(42));
+        }
+
+        // # Test case #3.
+        // Bob delegates to Alice, then Alice re-delegates to Charlie.
+        {
+            assert!(super::delegate(&mut votes, &map, bob(), alice(), 2).is_some());
+            assert_eq!(
+                super::get_votes(&votes, alice()),
+                U256::exp10(42) + U256::exp10(41)
+            );
+
+            assert_eq!(
+                super::delegate(&mut votes, &map, alice(), charlie(), 3),
+                Some((
+                    alice(),
+                    vec![
+                        (alice(), U256::exp10(42) + U256::exp10(41), U256::exp10(41)),
+                        (charlie(), U256::zero(), U256::exp10(42)),
+                    ]
+                ))
+            );
+
+            assert_eq!(super::get_votes(&votes, alice()), U256::exp10(41));
+            assert_eq!(super::get_votes(&votes, charlie()), U256::exp10(42));
+        }
+
+        // # Test case #4.
+        // Delegating to zero removes votes.
+        {
+            assert!(super::delegate(&mut votes, &map, alice(), ActorId::zero(), 4).is_some());
+
+            assert_eq!(super::delegates(&votes, alice()), ActorId::zero());
+            assert!(super::get_votes(&votes, charlie()).is_zero());
+        }
+
+        // # Test case #5.
+        // Past votes are looked up by block height.
+        {
+            assert_ok!(super::get_past_votes(&votes, alice(), 0, 5), U256::zero());
+            assert_ok!(
+                super::get_past_votes(&votes, alice(), 1, 5),
+                U256::exp10(42)
+            );
+            assert_ok!(
+                super::get_past_votes(&votes, alice(), 2, 5),
+                U256::exp10(42) + U256::exp10(41)
+            );
+            assert_ok!(
+                super::get_past_votes(&votes, charlie(), 3, 5),
+                U256::exp10(42)
+            );
+            assert_ok!(super::get_past_votes(&votes, charlie(), 4, 5), U256::zero());
+            assert_err!(
+                super::get_past_votes(&votes, charlie(), 5, 5),
+                Error::FutureLookup
+            );
+        }
+    }
+
+    #[test]
+    fn export_state() {
+        // Initializing thread logger.
+        let _ = env_logger::try_init();
+
+        // Creating maps with balances of Alice and Bob and Alice's approves.
+        let amap = allowances_map([
+            (alice(), bob(), U256::exp10(24)),
+            (alice(), charlie(), U256::exp10(12)),
+        ]);
+        let bmap = balances_map([(alice(), U256::exp10(42)), (bob(), U256::exp10(24))]);
+
+        let holders = [alice(), bob()];
+        let approvals = [(alice(), bob()), (alice(), charlie())];
+
+        // # Test case #1.
+        // First chunk points to the next one.
+        {
+            let chunk = super::export_state(&bmap, &amap, holders, approvals, state_meta(), 0, 1);
+
+            assert_eq!(chunk.version, STATE_VERSION);
+            assert_eq!(chunk.offset, 0);
+            assert_eq!(chunk.meta, state_meta());
+            assert_eq!(chunk.balances, vec![(alice(), U256::exp10(42))]);
+            assert_eq!(
+                chunk.allowances,
+                vec![(alice(), bob(), U256::exp10(24), None)]
+            );
+            assert_eq!(chunk.next, Some(1));
+        }
+
+        // # Test case #2.
+        // Last chunk has no next one.
+        {
+            let chunk = super::export_state(&bmap, &amap, holders, approvals, state_meta(), 1, 1);
+
+            assert_eq!(chunk.balances, vec![(bob(), U256::exp10(24))]);
+            assert_eq!(
+                chunk.allowances,
+                vec![(alice(), charlie(), U256::exp10(12), None)]
+            );
+            assert_eq!(chunk.next, None);
+
+            let chunk = super::export_state(&bmap, &amap, holders, approvals, state_meta(), 0, 2);
+
+            assert_eq!(chunk.balances.len(), 2);
+            assert_eq!(chunk.allowances.len(), 2);
+            assert_eq!(chunk.next, None);
+        }
+    }
+
+    #[test]
+    fn freeze() {
+        // Initializing thread logger.
+        let _ = env_logger::try_init();
+
+        // Creating empty set.
+        let mut set = blocklist_set([]);
+
+        // # Test case #1.
+        // Alice isn't frozen by default.
+        {
+            assert!(!super::is_frozen(&set, alice()));
+            assert_ok!(super::ensure_not_frozen(&set, alice()), ());
+        }
+
+        // # Test case #2.
+        // Alice is frozen.
+        {
+            assert!(super::freeze(&mut set, alice()));
+            assert!(super::is_frozen(&set, alice()));
+            assert_err!(
+                super::ensure_not_frozen(&set, alice()),
+                Error::AccountFrozen
+            );
+        }
+
+        // # Test case #3.
+        // Freezing frozen account is noop.
+        {
+            assert!(!super::freeze(&mut set, alice()));
+            assert!(super::is_frozen(&set, alice()));
+        }
+
+        // # Test case #4.
+        // Alice is unfrozen.
+        {
+            assert!(super::unfreeze(&mut set, alice()));
+            assert!(!super::is_frozen(&set, alice()));
+        }
+
+        // # Test case #5.
+        // Unfreezing not frozen account is noop.
+        {
+            assert!(!super::unfreeze(&mut set, alice()));
+            assert!(!super::is_frozen(&set, alice()));
+        }
+    }
+
+    #[test]
+    fn holders() {
+        // Initializing thread logger.
+        let _ = env_logger::try_init();
+
+        // Creating map with balances of Alice, Bob and Charlie.
+        let mut map = balances_map([
+            (alice(), U256::exp10(24)),
+            (bob(), U256::exp10(42)),
+            (charlie(), U256::exp10(24)),
+        ]);
+
+        // # Test case #1.
+        // Holders are paginated in given order.
+        {
+            let accounts = [alice(), bob(), charlie()];
+
+            assert_eq!(super::holders_count(&map), 3);
+            assert_eq!(
+                super::holders(&map, accounts, 0, 2),
+                vec![(alice(), U256::exp10(24)), (bob(), U256::exp10(42))]
+            );
+            assert_eq!(
+                super::holders(&map, accounts, 2, 2),
+                vec![(charlie(), U256::exp10(24))]
+            );
+            assert!(super::holders(&map, accounts, 3, 2).is_empty());
+        }
+
+        // # Test case #2.
+        // Top holders are sorted by balance, then by account.
+        {
+            assert_eq!(
+                super::top_holders(&map, 2),
+                vec![(bob(), U256::exp10(42)), (alice(), U256::exp10(24))]
+            );
+            assert_eq!(super::top_holders(&map, 5).len(), 3);
+            assert!(super::top_holders(&map, 0).is_empty());
+        }
+
+        // # Test case #3.
+        // Emptied account is no longer a holder.
+        {
+            assert_ok!(
+                super::transfer(&mut map, &LocksMap::new(), alice(), bob(), U256::exp10(24)),
+                true
+            );
+
+            assert_eq!(super::holders_count(&map), 2);
+            assert_eq!(
+                super::holders(&map, [alice(), bob(), charlie()], 0, 2),
+                vec![
+                    (bob(), U256::exp10(42) + U256::exp10(24)),
+                    (charlie(), U256::exp10(24))
+                ]
+            );
+        }
+    }
+
+    #[test]
+    fn import_state() {
+        // Initializing thread logger.
+        let _ = env_logger::try_init();
+
+        // Creating source maps with balances of Alice and Bob and Alice's approve.
+        let amap = allowances_map([(alice(), bob(), U256::exp10(24))]);
+        let bmap = balances_map([(alice(), U256::exp10(42)), (bob(), U256::exp10(24))]);
+
+        let export = |offset| {
+            super::export_state(
+                &bmap,
+                &amap,
+                [alice(), bob()],
+                [(alice(), bob())],
+                state_meta(),
+                offset,
+                1,
+            )
+        };
+
+        // Creating empty target maps with import started.
+        let mut amap = allowances_map([]);
+        let mut bmap = balances_map([]);
+        let mut total_supply = U256::zero();
+        let mut pending = Some((0, state_meta()));
+
+        // # Test case #1.
+        // Chunk of unsupported version, out of order or of another token is rejected.
+        {
+            let mut chunk = export(0);
+            chunk.version += 1;
+
+            assert_err!(
+                super::import_state(
+                    &mut bmap,
+                    &mut amap,
+                    &mut total_supply,
+                    None,
+                    &mut pending,
+                    chunk
+                ),
+                Error::UnsupportedVersion
+            );
+            assert_err!(
+                super::import_state(
+                    &mut bmap,
+                    &mut amap,
+                    &mut total_supply,
+                    None,
+                    &mut pending,
+                    export(1)
+                ),
+                Error::StateMismatch
+            );
+
+            let mut chunk = export(0);
+            chunk.meta.symbol = "TOK".into();
+
+            assert_err!(
+                super::import_state(
+                    &mut bmap,
+                    &mut amap,
+                    &mut total_supply,
+                    None,
+                    &mut pending,
+                    chunk
+                ),
+                Error::StateMismatch
+            );
+            assert!(bmap.is_empty());
+        }
+
+        // # Test case #2.
+        // Balances not matching total supply are rejected.
+        {
+            let mut chunk = export(0);
+            chunk.next = None;
+
+            assert_err!(
+                super::import_state(
+                    &mut bmap,
+                    &mut amap,
+                    &mut total_supply,
+                    None,
+                    &mut pending,
+                    chunk
+                ),
+                Error::StateMismatch
+            );
+
+            let mut chunk = export(0);
+            chunk.balances.push((bob(), U256::exp10(42)));
+
+            assert_err!(
+                super::import_state(
+                    &mut bmap,
+                    &mut amap,
+                    &mut total_supply,
+                    None,
+                    &mut pending,
+                    chunk
+                ),
+                Error::StateMismatch
+            );
+            assert!(bmap.is_empty());
+        }
+
+        // # Test case #3.
+        // Total supply must fit cap.
+        {
+            assert_err!(
+                super::import_state(
+                    &mut bmap,
+                    &mut amap,
+                    &mut total_supply,
+                    Some(U256::exp10(42)),
+                    &mut pending,
+                    export(0)
+                ),
+                Error::CapExceeded
+            );
+        }
+
+        // # Test case #4.
+        // Chunks are imported in order until the last one.
+        {
+            assert_ok!(
+                super::import_state(
+                    &mut bmap,
+                    &mut amap,
+                    &mut total_supply,
+                    None,
+                    &mut pending,
+                    export(0)
+                ),
+                false
+            );
+            assert_eq!(pending, Some((1, state_meta())));
+
+            assert_ok!(
+                super::import_state(
+                    &mut bmap,
+                    &mut amap,
+                    &mut total_supply,
+                    None,
+                    &mut pending,
+                    export(1)
+                ),
+                true
+            );
+            assert_eq!(pending, None);
+
+            assert_eq!(total_supply, state_meta().total_supply);
+            assert_eq!(super::balance_of(&bmap, alice()), U256::exp10(42));
+            assert_eq!(super::balance_of(&bmap, bob()), U256::exp10(24));
+            assert_eq!(super::allowance(&amap, alice(), bob(), 0), U256::exp10(24));
+        }
+
+        // # Test case #5.
+        // Import is closed once complete.
+        {
+            assert_err!(
+                super::import_state(
+                    &mut bmap,
+                    &mut amap,
+                    &mut total_supply,
+                    None,
+                    &mut pending,
+                    export(1)
+                ),
+                Error::ImportClosed
+            );
+        }
+    }
+
+    #[test]
+    fn increase_allowance() {
+        // Initializing thread logger.
+        let _ = env_logger::try_init();
+
+        // Creating empty map.
+        let mut map = allowances_map([]);
+
+        // # Test case #1.
+        // Allowance from Alice to Bob doesn't exist and created.
+        {
+            assert_ok!(
+                super::increase_allowance(&mut map, alice(), bob(), U256::exp10(20), 0),
+                true
+            );
+            assert_eq!(super::allowance(&map, alice(), bob(), 0), U256::exp10(20));
+        }
+
+        // # Test case #2.
+        // Allowance from Alice to Bob exists and increased.
+        {
+            assert_ok!(
+                super::increase_allowance(&mut map, alice(), bob(), U256::exp10(20), 0),
+                true
+            );
+            assert_eq!(
+                super::allowance(&map, alice(), bob(), 0),
+                U256::exp10(20).saturating_mul(2.into())
+            );
+        }
+
+        // # Test case #3.
+        // Allowance from Alice to Bob can't overflow.
+        {
+            assert_err!(
+                super::increase_allowance(&mut map, alice(), bob(), U256::MAX, 0),
+                Error::NumericOverflow
+            );
+            assert_eq!(
+                super::allowance(&map, alice(), bob(), 0),
+                U256::exp10(20).saturating_mul(2.into())
+            );
+        }
+
+        // # Test case #4.
+        // Increase is noop on zero delta or owner == spender.
+        {
+            assert_ok!(
+                super::increase_allowance(&mut map, alice(), bob(), U256::zero(), 0),
+                false
+            );
+            assert_ok!(
+                super::increase_allowance(&mut map, alice(), alice(), U256::exp10(42), 0),
+                false
+            );
+            assert!(super::allowance(&map, alice(), alice(), 0).is_zero());
+        }
+    }
+
+    #[test]
+    fn lock() {
+        // Initializing thread logger.
+        let _ = env_logger::try_init();
+
+        // Creating map with medium balance belonged to Alice.
+        let mut map = balances_map([(alice(), U256::exp10(42))]);
+
+        let mut locks = LocksMap::new();
+
+        // # Test case #1.
+        // Lock must expire in future.
+        {
+            assert_err!(
+                super::lock(&map, &mut locks, alice(), U256::exp10(41), 10, 10),
+                Error::InvalidLock
+            );
+            assert!(locks.is_empty());
+        }
+
+        // # Test case #2.
+        // Alice can't lock more than she has.
+        {
+            assert_err!(
+                super::lock(&map, &mut locks, alice(), U256::exp10(43), 20, 10),
+                Error::InsufficientBalance
+            );
+            assert!(locks.is_empty());
+        }
+
+        // # Test case #3.
+        // Alice locks part of her balance, so she can't spend it.
+        {
+            assert_ok!(
+                super::lock(&map, &mut locks, alice(), U256::exp10(41) * 9, 20, 10),
+                true
+            );
+
+            assert_eq!(
+                super::locked_balance_of(&locks, alice(), 10),
+                U256::exp10(41) * 9
+            );
+            assert_eq!(super::balance_of(&map, alice()), U256::exp10(42));
+
+            assert_err!(
+                super::transfer(&mut map, &locks, alice(), bob(), U256::exp10(41) * 2),
+                Error::BalanceLocked
+            );
+            assert_err!(
+                super::lock(&map, &mut locks, alice(), U256::exp10(41) * 2, 20, 10),
+                Error::BalanceLocked
+            );
+
+            assert_ok!(
+                super::transfer(&mut map, &locks, alice(), bob(), U256::exp10(41)),
+                true
+            );
+        }
+
+        // # Test case #4.
+        // Expired locks are dropped, so balance is spendable again.
+        {
+            assert!(super::locked_balance_of(&locks, alice(), 20).is_zero());
+
+            super::unlock_expired(&mut locks, alice(), 19);
+            assert!(!locks.is_empty());
+
+            super::unlock_expired(&mut locks, alice(), 20);
+            assert!(locks.is_empty());
+
+            assert_ok!(
+                super::burn(
+                    &mut map,
+                    &locks,
+                    &mut U256::exp10(42),
+                    alice(),
+                    U256::exp10(41) * 9
+                ),
+                true
+            );
+        }
+
+        // # Test case #5.
+        // Locking zero is noop.
+        {
+            assert_ok!(
+                super::lock(&map, &mut locks, bob(), U256::zero(), 20, 10),
+                false
+            );
+            assert!(locks.is_empty());
+        }
+
+        // # Test case #6.
+        // Locks with the same expiry are merged.
+        {
+            assert_ok!(
+                super::lock(&map, &mut locks, bob(), U256::one(), 20, 10),
+                true
+            );
+            assert_ok!(
+                super::lock(&map, &mut locks, bob(), U256::one(), 20, 10),
+                true
+            );
+
+            assert_eq!(locks[&bob()].own, vec![(20, U256::from(2))]);
+        }
+
+        // # Test case #7.
+        // Number of locks is capped, while merging into existing ones is still allowed.
+        {
+            for until in 21..20 + MAX_LOCKS as u32 {
+                assert_ok!(
+                    super::lock(&map, &mut locks, bob(), U256::one(), until, 10),
+                    true
+                );
+            }
+
+            assert_err!(
+                super::lock(&map, &mut locks, bob(), U256::one(), 100, 10),
+                Error::TooManyLocks
+            );
+            assert_eq!(locks[&bob()].own.len(), MAX_LOCKS);
+
+            assert_ok!(
+                super::lock(&map, &mut locks, bob(), U256::one(), 20, 10),
+                true
+            );
+            assert_eq!(locks[&bob()].own.len(), MAX_LOCKS);
+        }
+    }
+
+    #[test]
+    fn mint() {
+        // Initializing thread logger.
+        let _ = env_logger::try_init();
+
+        // Creating map with medium balance belonged to Bob.
+        let mut map = balances_map([(bob(), U256::exp10(42))]);
+        let mut total_supply = U256::exp10(42);
+
+        // # Test case #1.
+        // Minting to Alice, when Alice's account doesn't exist.
+        {
+            assert_ok!(
+                super::mint(&mut map, &mut total_supply, None, alice(), U256::exp10(20)),
+                true
+            );
+
+            assert_eq!(super::balance_of(&map, alice()), U256::exp10(20));
+            assert_eq!(total_supply, total_supply_of(&map));
+        }
+
+        // # Test case #2.
+        // Minting to Bob, when Bob's account exists.
+        {
+            assert_ok!(
+                super::mint(&mut map, &mut total_supply, None, bob(), U256::exp10(20)),
+                true
+            );
+
+            assert_eq!(
+                super::balance_of(&map, bob()),
+                U256::exp10(42) + U256::exp10(20)
+            );
+            assert_eq!(total_supply, total_supply_of(&map));
+        }
+
+        // # Test case #3.
+        // Minting to Charlie, causing total supply numeric overflow.
+        {
+            assert_err!(
+                super::mint(&mut map, &mut total_supply, None, charlie(), U256::MAX),
+                Error::NumericOverflow
+            );
+
+            assert!(super::balance_of(&map, charlie()).is_zero());
+            assert_eq!(total_supply, total_supply_of(&map));
+        }
+
+        // # Test case #4.
+        // Mint is always noop when value is zero.
+        {
+            assert_ok!(
+                super::mint(&mut map, &mut total_supply, None, charlie(), U256::zero()),
+                false
+            );
+
+            assert!(!map.contains_key(&charlie()));
+            assert_eq!(total_supply, total_supply_of(&map));
+        }
+
+        // # Test case #5.
+        // Minting to Charlie, causing cap to be exceeded.
+        {
+            let cap = Some(total_supply + U256::exp10(20));
+
+            assert_err!(
+                super::mint(
+                    &mut map,
+                    &mut total_supply,
+                    cap,
+                    charlie(),
+                    U256::exp10(20) + 1
+                ),
+                Error::CapExceeded
+            );
+
+            assert!(super::balance_of(&map, charlie()).is_zero());
+            assert_eq!(total_supply, total_supply_of(&map));
+
+            // Reaching cap exactly is allowed.
+            assert_ok!(
+                super::mint(&mut map, &mut total_supply, cap, charlie(), U256::exp10(20)),
+                true
+            );
+
+            assert_eq!(super::balance_of(&map, charlie()), U256::exp10(20));
+            assert_eq!(Some(total_supply), cap);
+        }
+    }
+
+    #[test]
+    fn permit() {
+        // Initializing thread logger.
+        let _ = env_logger::try_init();
+
+        // Creating empty allowances and nonces maps.
+        let mut amap = allowances_map([]);
+        let mut nmap = NoncesMap::new();
+
+        let domain_separator = super::domain_separator("Vara Network", 42u64.into());
+
+        let permit_of = |owner: ActorId, nonce: u64| Permit {
+            domain_separator,
+            owner,
+            spender: bob(),
+            value: U256::exp10(42),
+            nonce,
+            deadline: 100,
+        };
+
+        let owner = ed25519_signer(1).0;
+
+        // # Test case #1.
+        // Permit is rejected after deadline.
+        {
+            let permit = permit_of(owner, 0);
+            let signature = ed25519_signer(1).1(&permit.encode());
+
+            assert_err!(
+                super::permit(&mut amap, &mut nmap, &permit, &signature, 101),
+                Error::PermitExpired
+            );
+            assert!(super::allowance(&amap, owner, bob(), 0).is_zero());
+            assert_eq!(super::nonce_of(&nmap, owner), 0);
+        }
+
+        // # Test case #2.
+        // Permit is rejected with wrong nonce.
+        {
+            let permit = permit_of(owner, 1);
+            let signature = ed25519_signer(1).1(&permit.encode());
+
+            assert_err!(
+                super::permit(&mut amap, &mut nmap, &permit, &signature, 100),
+                Error::InvalidNonce
+            );
+            assert!(super::allowance(&amap, owner, bob(), 0).is_zero());
+            assert_eq!(super::nonce_of(&nmap, owner), 0);
+        }
+
+        // # Test case #3.
+        // Permit is rejected if signed not by owner.
+        {
+            let permit = permit_of(owner, 0);
+            let signature = ed25519_signer(2).1(&permit.encode());
+
+            assert_err!(
+                super::permit(&mut amap, &mut nmap, &permit, &signature, 100),
+                Error::InvalidSignature
+            );
+            assert!(super::allowance(&amap, owner, bob(), 0).is_zero());
+            assert_eq!(super::nonce_of(&nmap, owner), 0);
+        }
+
+        // # Test case #4.
+        // Permit signed with ed25519 is applied.
+        {
+            let permit = permit_of(owner, 0);
+            let signature = ed25519_signer(1).1(&permit.encode());
+
+            assert_ok!(
+                super::permit(&mut amap, &mut nmap, &permit, &signature, 100),
+                true
+            );
+            assert_eq!(super::allowance(&amap, owner, bob(), 0), U256::exp10(42));
+            assert_eq!(super::nonce_of(&nmap, owner), 1);
+
+            // Permit can't be replayed.
+            assert_err!(
+                super::permit(&mut amap, &mut nmap, &permit, &signature, 100),
+                Error::InvalidNonce
+            );
+        }
+
+        // # Test case #5.
+        // Permit signed with sr25519 is applied.
+        {
+            let (owner, sign) = sr25519_signer(3);
+
+            let permit = permit_of(owner, 0);
+            let signature = sign(&permit.encode());
+
+            assert_ok!(
+                super::permit(&mut amap, &mut nmap, &permit, &signature, 100),
+                true
+            );
+            assert_eq!(super::allowance(&amap, owner, bob(), 0), U256::exp10(42));
+            assert_eq!(super::nonce_of(&nmap, owner), 1);
+        }
+
+        // # Test case #6.
+        // Permit signed for another domain is rejected.
+        {
+            let permit = Permit {
+                domain_separator: super::domain_separator("Vara Network", 43u64.into()),
+                ..permit_of(owner, 1)
+            };
+            let signature = ed25519_signer(1).1(&permit.encode());
+
+            assert_err!(
+                super::permit(&mut amap, &mut nmap, &permit_of(owner, 1), &signature, 100),
+                Error::InvalidSignature
+            );
+            assert_eq!(super::nonce_of(&nmap, owner), 1);
         }
     }
 
     #[test]
-    fn approve() {
+    fn release() {
         // Initializing thread logger.
         let _ = env_logger::try_init();
 
-        // Creating empty map.
-        let mut map = allowances_map([]);
-        assert!(map.is_empty());
+        // Creating map with medium balance belonged to Alice.
+        let mut map = balances_map([(alice(), U256::exp10(42))]);
+
+        // Creating schedule for Bob: starts at 100, cliff at 200, ends at 500.
+        let mut vesting = VestingMap::new();
+        let schedule = schedule_of(bob(), U256::exp10(40) * 4);
+
+        assert_ok!(
+            super::create_vesting(
+                &mut vesting,
+                &mut map,
+                &LocksMap::new(),
+                dave(),
+                alice(),
+                schedule
+            ),
+            true
+        );
 
         // # Test case #1.
-        // Allowance from Alice to Bob doesn't exist and created.
+        // Nothing is vested before the cliff.
         {
-            assert!(super::approve(&mut map, alice(), bob(), U256::exp10(42)));
-            assert_eq!(super::allowance(&map, alice(), bob()), U256::exp10(42));
+            assert!(super::vested_amount(&vesting, bob(), 50).is_zero());
+            assert!(super::vested_amount(&vesting, bob(), 199).is_zero());
+
+            assert_ok!(
+                super::release(&mut vesting, &mut map, &LocksMap::new(), dave(), bob(), 199),
+                U256::zero()
+            );
+            assert!(super::balance_of(&map, bob()).is_zero());
+        }
+
+        // # Test case #2.
+        // Vested amount grows linearly since start after the cliff.
+        {
+            assert_eq!(super::vested_amount(&vesting, bob(), 200), U256::exp10(40));
+            assert_eq!(
+                super::releasable_amount(&vesting, bob(), 300),
+                U256::exp10(40) * 2
+            );
+
+            assert_ok!(
+                super::release(&mut vesting, &mut map, &LocksMap::new(), dave(), bob(), 300),
+                U256::exp10(40) * 2
+            );
+
+            assert_eq!(super::balance_of(&map, bob()), U256::exp10(40) * 2);
+            assert_eq!(
+                super::vested_amount(&vesting, bob(), 300),
+                U256::exp10(40) * 2
+            );
+            assert!(super::releasable_amount(&vesting, bob(), 300).is_zero());
+        }
+
+        // # Test case #3.
+        // Everything is vested after the end, so schedule is removed.
+        {
+            assert_ok!(
+                super::release(
+                    &mut vesting,
+                    &mut map,
+                    &LocksMap::new(),
+                    dave(),
+                    bob(),
+                    1000
+                ),
+                U256::exp10(40) * 2
+            );
+
+            assert_eq!(super::balance_of(&map, bob()), U256::exp10(40) * 4);
+            assert!(super::balance_of(&map, dave()).is_zero());
+            assert!(vesting.is_empty());
+        }
+
+        // # Test case #4.
+        // Charlie has no schedule to release.
+        {
+            assert_err!(
+                super::release(
+                    &mut vesting,
+                    &mut map,
+                    &LocksMap::new(),
+                    dave(),
+                    charlie(),
+                    1000
+                ),
+                Error::VestingNotFound
+            );
+        }
+    }
+
+    #[test]
+    fn revoke_all() {
+        // Initializing thread logger.
+        let _ = env_logger::try_init();
+
+        // Creating map with Alice's approves to Bob and Charlie and Bob's one to Alice.
+        let mut map = allowances_map([
+            (alice(), bob(), U256::exp10(42)),
+            (alice(), charlie(), U256::exp10(24)),
+            (bob(), alice(), U256::exp10(12)),
+        ]);
+
+        // # Test case #1.
+        // Only owner's existing allowances are revoked.
+        {
+            assert_eq!(
+                super::revoke_all(&mut map, alice(), [bob(), charlie(), dave()]),
+                vec![bob(), charlie()]
+            );
+
+            assert!(super::allowance(&map, alice(), bob(), 0).is_zero());
+            assert!(super::allowance(&map, alice(), charlie(), 0).is_zero());
+            assert_eq!(super::allowance(&map, bob(), alice(), 0), U256::exp10(12));
+        }
+
+        // # Test case #2.
+        // Nothing to revoke.
+        {
+            assert!(super::revoke_all(&mut map, alice(), [bob(), charlie()]).is_empty());
+            assert_eq!(map.len(), 1);
+        }
+    }
+
+    #[test]
+    fn revoke_vesting() {
+        // Initializing thread logger.
+        let _ = env_logger::try_init();
+
+        // Creating map with medium balance belonged to Alice.
+        let mut map = balances_map([(alice(), U256::exp10(42))]);
+
+        // Creating revocable schedule for Bob and irrevocable one for Charlie.
+        let mut vesting = VestingMap::new();
+
+        for (beneficiary, revocable) in [(bob(), true), (charlie(), false)] {
+            let schedule = VestingSchedule {
+                revocable,
+                ..schedule_of(beneficiary, U256::exp10(40) * 4)
+            };
+
+            assert_ok!(
+                super::create_vesting(
+                    &mut vesting,
+                    &mut map,
+                    &LocksMap::new(),
+                    dave(),
+                    alice(),
+                    schedule
+                ),
+                true
+            );
+        }
+
+        // # Test case #1.
+        // Charlie's schedule can't be revoked.
+        {
+            assert_err!(
+                super::revoke_vesting(
+                    &mut vesting,
+                    &mut map,
+                    &LocksMap::new(),
+                    dave(),
+                    charlie(),
+                    alice(),
+                    300
+                ),
+                Error::NotRevocable
+            );
+        }
+
+        // # Test case #2.
+        // Bob's schedule is revoked with vested part kept for him.
+        {
+            assert_ok!(
+                super::revoke_vesting(
+                    &mut vesting,
+                    &mut map,
+                    &LocksMap::new(),
+                    dave(),
+                    bob(),
+                    alice(),
+                    300
+                ),
+                U256::exp10(40) * 2
+            );
+
+            assert_eq!(
+                super::balance_of(&map, alice()),
+                U256::exp10(42) - U256::exp10(40) * 6
+            );
+            assert_eq!(
+                super::releasable_amount(&vesting, bob(), 300),
+                U256::exp10(40) * 2
+            );
+
+            assert_ok!(
+                super::release(&mut vesting, &mut map, &LocksMap::new(), dave(), bob(), 300),
+                U256::exp10(40) * 2
+            );
+            assert!(!vesting.contains_key(&bob()));
+        }
+
+        // # Test case #3.
+        // Revoked schedule is gone.
+        {
+            assert_err!(
+                super::revoke_vesting(
+                    &mut vesting,
+                    &mut map,
+                    &LocksMap::new(),
+                    dave(),
+                    bob(),
+                    alice(),
+                    300
+                ),
+                Error::VestingNotFound
+            );
+        }
+    }
+
+    #[test]
+    fn seize() {
+        // Initializing thread logger.
+        let _ = env_logger::try_init();
+
+        // Creating blocklist with frozen Alice and Charlie.
+        let set = blocklist_set([alice(), charlie()]);
+
+        // Creating map with medium balances belonged to Alice and Bob.
+        let mut map = balances_map([(alice(), U256::exp10(42)), (bob(), U256::exp10(42))]);
+
+        // # Test case #1.
+        // Bob's balance can't be seized since he isn't frozen.
+        {
+            assert_err!(
+                super::seize(&set, &mut map, &mut LocksMap::new(), bob(), dave()),
+                Error::AccountNotFrozen
+            );
+
+            assert_eq!(super::balance_of(&map, bob()), U256::exp10(42));
+            assert!(super::balance_of(&map, dave()).is_zero());
+        }
+
+        // # Test case #2.
+        // Alice's balance can't be seized to frozen Charlie.
+        {
+            assert_err!(
+                super::seize(&set, &mut map, &mut LocksMap::new(), alice(), charlie()),
+                Error::AccountFrozen
+            );
+
+            assert_eq!(super::balance_of(&map, alice()), U256::exp10(42));
+            assert!(super::balance_of(&map, charlie()).is_zero());
+        }
+
+        // # Test case #3.
+        // Alice's balance is seized to Dave.
+        {
+            assert_ok!(
+                super::seize(&set, &mut map, &mut LocksMap::new(), alice(), dave()),
+                U256::exp10(42)
+            );
+
+            assert!(super::balance_of(&map, alice()).is_zero());
+            assert_eq!(super::balance_of(&map, dave()), U256::exp10(42));
+        }
+
+        // # Test case #4.
+        // Seizing empty balance is noop.
+        {
+            assert_ok!(
+                super::seize(&set, &mut map, &mut LocksMap::new(), alice(), dave()),
+                U256::zero()
+            );
+
+            assert!(super::balance_of(&map, alice()).is_zero());
+            assert_eq!(super::balance_of(&map, dave()), U256::exp10(42));
+        }
+
+        // # Test case #5.
+        // Failed seizure keeps locks of the frozen account.
+        {
+            let set = blocklist_set([bob()]);
+            let mut locks = LocksMap::from([(
+                bob(),
+                Locks {
+                    own: vec![(u32::MAX, U256::one())],
+                    received: vec![],
+                },
+            )]);
+
+            map.insert(dave(), U256::MAX.try_into().unwrap());
+
+            assert_err!(
+                super::seize(&set, &mut map, &mut locks, bob(), dave()),
+                Error::NumericOverflow
+            );
+
+            assert_eq!(super::balance_of(&map, bob()), U256::exp10(42));
+            assert!(locks.contains_key(&bob()));
+        }
+    }
+
+    #[test]
+    fn snapshot() {
+        // Initializing thread logger.
+        let _ = env_logger::try_init();
+
+        // Creating map with medium balance belonged to Alice.
+        let mut map = balances_map([(alice(), U256::exp10(42))]);
+        let mut total_supply = total_supply_of(&map);
+
+        let mut snapshots = Snapshots::default();
+        let mut votes = Votes::default();
+
+        // # Test case #1.
+        // No snapshots taken yet, so nothing to query and to record.
+        {
+            assert_err!(
+                super::balance_of_at(&snapshots, &map, alice(), 0),
+                Error::SnapshotNotFound
+            );
+            assert_err!(
+                super::total_supply_at(&snapshots, total_supply, 1),
+                Error::SnapshotNotFound
+            );
+
+            super::record_snapshot(&mut snapshots, &map, total_supply, [alice()]);
+            assert_eq!(snapshots, Snapshots::default());
         }
 
         // # Test case #2.
-        // Allowance from Alice to Bob exist and changed.
+        // Snapshot with no changes since reports current values.
         {
-            assert!(super::approve(&mut map, alice(), bob(), U256::exp10(24)));
-            assert_eq!(super::allowance(&map, alice(), bob()), U256::exp10(24));
+            assert_ok!(super::snapshot(&mut snapshots), 1);
+
+            assert_ok!(
+                super::balance_of_at(&snapshots, &map, alice(), 1),
+                U256::exp10(42)
+            );
+            assert_ok!(
+                super::total_supply_at(&snapshots, total_supply, 1),
+                U256::exp10(42)
+            );
         }
 
         // # Test case #3.
-        // Allowance from Alice to Bob exists and not changed.
+        // Pre-change values are kept for the snapshot, being recorded on writes.
         {
-            assert!(!super::approve(&mut map, alice(), bob(), U256::exp10(24)));
-            assert_eq!(super::allowance(&map, alice(), bob()), U256::exp10(24));
+            let mut balances =
+                TrackedBalances::new(&mut map, &mut snapshots, total_supply, &mut votes, 0);
+            assert_ok!(
+                super::transfer(
+                    &mut balances,
+                    &LocksMap::new(),
+                    alice(),
+                    bob(),
+                    U256::exp10(41)
+                ),
+                true
+            );
+
+            let mut balances =
+                TrackedBalances::new(&mut map, &mut snapshots, total_supply, &mut votes, 0);
+            assert_ok!(
+                super::burn(
+                    &mut balances,
+                    &LocksMap::new(),
+                    &mut total_supply,
+                    bob(),
+                    U256::exp10(41)
+                ),
+                true
+            );
+
+            assert_ok!(
+                super::balance_of_at(&snapshots, &map, alice(), 1),
+                U256::exp10(42)
+            );
+            assert_ok!(
+                super::balance_of_at(&snapshots, &map, bob(), 1),
+                U256::zero()
+            );
+            assert_ok!(
+                super::total_supply_at(&snapshots, total_supply, 1),
+                U256::exp10(42)
+            );
         }
 
         // # Test case #4.
-        // Allowance from Alice to Bob exists and removed.
+        // Failed mutation records nothing.
         {
-            assert!(super::approve(&mut map, alice(), bob(), U256::zero()));
-            assert!(super::allowance(&map, alice(), bob()).is_zero());
+            assert_ok!(super::snapshot(&mut snapshots), 2);
+
+            let before = snapshots.clone();
+
+            let mut balances =
+                TrackedBalances::new(&mut map, &mut snapshots, total_supply, &mut votes, 0);
+            assert_err!(
+                super::transfer(&mut balances, &LocksMap::new(), bob(), alice(), U256::one()),
+                Error::InsufficientBalance
+            );
+
+            assert_eq!(snapshots, before);
         }
 
         // # Test case #5.
-        // Allowance from Alice to Bob doesn't exists and not created.
+        // Older snapshots stay untouched by newer ones.
         {
-            assert!(!super::approve(&mut map, alice(), bob(), U256::zero()));
-            assert!(super::allowance(&map, alice(), bob()).is_zero());
-        }
+            assert_ok!(super::snapshot(&mut snapshots), 3);
 
-        // # Test case #6.
-        // Allowance is always noop on owner == spender.
-        {
-            assert!(!super::approve(&mut map, alice(), alice(), U256::exp10(42)));
-            assert!(super::allowance(&map, alice(), alice()).is_zero());
+            let mut balances =
+                TrackedBalances::new(&mut map, &mut snapshots, total_supply, &mut votes, 0);
+            assert_ok!(
+                super::burn(
+                    &mut balances,
+                    &LocksMap::new(),
+                    &mut total_supply,
+                    alice(),
+                    U256::exp10(41)
+                ),
+                true
+            );
 
-            assert!(!super::approve(&mut map, alice(), alice(), U256::exp10(24)));
-            assert!(super::allowance(&map, alice(), alice()).is_zero());
+            let alice_at_2 = U256::exp10(42) - U256::exp10(41);
 
-            assert!(!super::approve(&mut map, alice(), alice(), U256::zero()));
-            assert!(super::allowance(&map, alice(), alice()).is_zero());
+            assert_ok!(
+                super::balance_of_at(&snapshots, &map, alice(), 1),
+                U256::exp10(42)
+            );
+            assert_ok!(
+                super::balance_of_at(&snapshots, &map, alice(), 2),
+                alice_at_2
+            );
+            assert_ok!(
+                super::balance_of_at(&snapshots, &map, alice(), 3),
+                alice_at_2
+            );
+            assert_ok!(
+                super::total_supply_at(&snapshots, total_supply, 3),
+                alice_at_2
+            );
+            assert_err!(
+                super::balance_of_at(&snapshots, &map, alice(), 4),
+                Error::SnapshotNotFound
+            );
         }
     }
 
     #[test]
-    fn balance_of() {
+    fn tracked_balances() {
         // Initializing thread logger.
         let _ = env_logger::try_init();
 
-        // Creating map with one single balance belonged to Alice.
-        let map = balances_map([(alice(), U256::exp10(42))]);
+        // Creating map with medium balance belonged to Alice.
+        let mut map = balances_map([(alice(), U256::exp10(42))]);
+        let mut total_supply = total_supply_of(&map);
+
+        let mut snapshots = Snapshots::default();
+
+        // Alice and Dave delegate to Charlie, Bob to himself.
+        let mut votes = Votes::default();
+        let _ = super::delegate(&mut votes, &map, alice(), charlie(), 1);
+        let _ = super::delegate(&mut votes, &map, bob(), bob(), 1);
+        let _ = super::delegate(&mut votes, &map, dave(), charlie(), 1);
 
         // # Test case #1.
-        // Balance is returned if exists.
+        // Transfer moves votes between delegates.
         {
-            assert!(map.contains_key(&alice()));
-            assert_eq!(super::balance_of(&map, alice()), U256::exp10(42));
+            let mut balances =
+                TrackedBalances::new(&mut map, &mut snapshots, total_supply, &mut votes, 2);
+            assert_ok!(
+                super::transfer(
+                    &mut balances,
+                    &LocksMap::new(),
+                    alice(),
+                    bob(),
+                    U256::exp10(41)
+                ),
+                true
+            );
+
+            assert_eq!(
+                balances.finish(),
+                (
+                    vec![alice(), bob()],
+                    vec![
+                        (
+                            charlie(),
+                            U256::exp10(42),
+                            U256::exp10(42) - U256::exp10(41)
+                        ),
+                        (bob(), U256::zero(), U256::exp10(41)),
+                    ]
+                )
+            );
         }
 
         // # Test case #2.
-        // U256::zero() is returned if not exists.
+        // Transfer between accounts of the same delegate doesn't change votes.
         {
-            assert!(!map.contains_key(&bob()));
-            assert!(super::balance_of(&map, bob()).is_zero());
+            let mut balances =
+                TrackedBalances::new(&mut map, &mut snapshots, total_supply, &mut votes, 2);
+            assert_ok!(
+                super::transfer(
+                    &mut balances,
+                    &LocksMap::new(),
+                    alice(),
+                    dave(),
+                    U256::exp10(41)
+                ),
+                true
+            );
+
+            assert_eq!(balances.finish(), (vec![alice(), dave()], vec![]));
+            assert_eq!(
+                super::get_votes(&votes, charlie()),
+                U256::exp10(42) - U256::exp10(41)
+            );
+        }
+
+        // # Test case #3.
+        // Minting to and burning from non-delegating account doesn't change votes.
+        {
+            let mut balances =
+                TrackedBalances::new(&mut map, &mut snapshots, total_supply, &mut votes, 2);
+            assert_ok!(
+                super::mint(&mut balances, &mut total_supply, None, eve(), U256::one()),
+                true
+            );
+            assert_ok!(
+                super::burn(
+                    &mut balances,
+                    &LocksMap::new(),
+                    &mut total_supply,
+                    eve(),
+                    U256::one()
+                ),
+                true
+            );
+
+            assert_eq!(balances.finish(), (vec![eve()], vec![]));
+        }
+
+        // # Test case #4.
+        // Changes within single block are kept in one checkpoint.
+        {
+            let mut balances =
+                TrackedBalances::new(&mut map, &mut snapshots, total_supply, &mut votes, 2);
+            assert_ok!(
+                super::mint(
+                    &mut balances,
+                    &mut total_supply,
+                    None,
+                    bob(),
+                    U256::exp10(41)
+                ),
+                true
+            );
+
+            assert_eq!(
+                balances.finish().1,
+                vec![(bob(), U256::exp10(41), U256::exp10(41) * 2)]
+            );
+
+            assert_eq!(votes.checkpoints[&bob()], vec![(2, U256::exp10(41) * 2)]);
+            assert_eq!(super::get_votes(&votes, bob()), U256::exp10(41) * 2);
         }
     }
 
@@ -295,7 +4005,7 @@ mod tests {
             assert_eq!(super::balance_of(&map, bob()), U256::exp10(42));
 
             assert_err!(
-                super::transfer(&mut map, alice(), bob(), U256::exp10(20)),
+                super::transfer(&mut map, &LocksMap::new(), alice(), bob(), U256::exp10(20)),
                 Error::InsufficientBalance
             );
 
@@ -310,7 +4020,7 @@ mod tests {
             assert_eq!(super::balance_of(&map, bob()), U256::exp10(42));
 
             assert_err!(
-                super::transfer(&mut map, bob(), alice(), U256::exp10(50)),
+                super::transfer(&mut map, &LocksMap::new(), bob(), alice(), U256::exp10(50)),
                 Error::InsufficientBalance
             );
 
@@ -325,7 +4035,7 @@ mod tests {
             assert_eq!(super::balance_of(&map, dave()), U256::MAX);
 
             assert_err!(
-                super::transfer(&mut map, dave(), bob(), U256::MAX),
+                super::transfer(&mut map, &LocksMap::new(), dave(), bob(), U256::MAX),
                 Error::NumericOverflow
             );
 
@@ -340,7 +4050,7 @@ mod tests {
             assert_eq!(super::balance_of(&map, bob()), U256::exp10(42));
 
             assert_ok!(
-                super::transfer(&mut map, bob(), alice(), U256::exp10(10)),
+                super::transfer(&mut map, &LocksMap::new(), bob(), alice(), U256::exp10(10)),
                 true
             );
 
@@ -361,7 +4071,7 @@ mod tests {
             );
 
             assert_ok!(
-                super::transfer(&mut map, bob(), alice(), U256::exp10(10)),
+                super::transfer(&mut map, &LocksMap::new(), bob(), alice(), U256::exp10(10)),
                 true
             );
 
@@ -390,6 +4100,7 @@ mod tests {
             assert_ok!(
                 super::transfer(
                     &mut map,
+                    &LocksMap::new(),
                     bob(),
                     alice(),
                     U256::exp10(42) - U256::exp10(10).saturating_mul(2.into())
@@ -408,7 +4119,13 @@ mod tests {
             assert!(super::balance_of(&map, charlie()).is_zero());
 
             assert_ok!(
-                super::transfer(&mut map, alice(), charlie(), U256::exp10(42)),
+                super::transfer(
+                    &mut map,
+                    &LocksMap::new(),
+                    alice(),
+                    charlie(),
+                    U256::exp10(42)
+                ),
                 true
             );
 
@@ -421,14 +4138,26 @@ mod tests {
         {
             assert!(super::balance_of(&map, alice()).is_zero());
             assert_ok!(
-                super::transfer(&mut map, alice(), alice(), U256::exp10(42)),
+                super::transfer(
+                    &mut map,
+                    &LocksMap::new(),
+                    alice(),
+                    alice(),
+                    U256::exp10(42)
+                ),
                 false
             );
             assert!(super::balance_of(&map, alice()).is_zero());
 
             assert_eq!(super::balance_of(&map, charlie()), U256::exp10(42));
             assert_ok!(
-                super::transfer(&mut map, charlie(), charlie(), U256::exp10(42)),
+                super::transfer(
+                    &mut map,
+                    &LocksMap::new(),
+                    charlie(),
+                    charlie(),
+                    U256::exp10(42)
+                ),
                 false
             );
             assert_eq!(super::balance_of(&map, charlie()), U256::exp10(42));
@@ -441,19 +4170,35 @@ mod tests {
             assert_eq!(super::balance_of(&map, charlie()), U256::exp10(42));
 
             assert_ok!(
-                super::transfer(&mut map, alice(), charlie(), U256::zero()),
+                super::transfer(&mut map, &LocksMap::new(), alice(), charlie(), U256::zero()),
                 false
             );
             assert!(super::balance_of(&map, alice()).is_zero());
             assert_eq!(super::balance_of(&map, charlie()), U256::exp10(42));
 
             assert_ok!(
-                super::transfer(&mut map, charlie(), alice(), U256::zero()),
+                super::transfer(&mut map, &LocksMap::new(), charlie(), alice(), U256::zero()),
                 false
             );
             assert!(super::balance_of(&map, alice()).is_zero());
             assert_eq!(super::balance_of(&map, charlie()), U256::exp10(42));
         }
+
+        // # Test case #10.
+        // Transfer works the same over any balances store.
+        {
+            let mut map: BTreeMap<_, _> = balances_map([(alice(), U256::exp10(42))])
+                .into_iter()
+                .collect();
+
+            assert_ok!(
+                super::transfer(&mut map, &LocksMap::new(), alice(), bob(), U256::exp10(42)),
+                true
+            );
+            assert!(super::balance_of(&map, alice()).is_zero());
+            assert_eq!(super::balance_of(&map, bob()), U256::exp10(42));
+            assert!(!map.contains_key(&alice()));
+        }
     }
 
     // Since this uses [`super::transfer`] in underlying impl, it needs only
@@ -474,7 +4219,16 @@ mod tests {
         // With zero value nothing's changed.
         {
             assert_ok!(
-                super::transfer_from(&mut amap, &mut bmap, bob(), bob(), alice(), U256::zero()),
+                super::transfer_from(
+                    &mut amap,
+                    &mut bmap,
+                    &LocksMap::new(),
+                    bob(),
+                    bob(),
+                    alice(),
+                    U256::zero(),
+                    0
+                ),
                 false
             );
             assert!(super::balance_of(&bmap, alice()).is_zero());
@@ -485,7 +4239,16 @@ mod tests {
         // Bob doesn't need approve to transfer from self to Alice.
         {
             assert_ok!(
-                super::transfer_from(&mut amap, &mut bmap, bob(), bob(), alice(), U256::exp10(42)),
+                super::transfer_from(
+                    &mut amap,
+                    &mut bmap,
+                    &LocksMap::new(),
+                    bob(),
+                    bob(),
+                    alice(),
+                    U256::exp10(42),
+                    0
+                ),
                 true
             );
             assert_eq!(super::balance_of(&bmap, alice()), U256::exp10(42));
@@ -497,7 +4260,16 @@ mod tests {
         {
             assert!(super::balance_of(&bmap, bob()).is_zero());
             assert_ok!(
-                super::transfer_from(&mut amap, &mut bmap, bob(), bob(), bob(), U256::exp10(42)),
+                super::transfer_from(
+                    &mut amap,
+                    &mut bmap,
+                    &LocksMap::new(),
+                    bob(),
+                    bob(),
+                    bob(),
+                    U256::exp10(42),
+                    0
+                ),
                 false
             );
             assert!(super::balance_of(&bmap, bob()).is_zero());
@@ -507,10 +4279,12 @@ mod tests {
                 super::transfer_from(
                     &mut amap,
                     &mut bmap,
+                    &LocksMap::new(),
                     alice(),
                     alice(),
                     alice(),
-                    U256::exp10(42)
+                    U256::exp10(42),
+                    0
                 ),
                 false
             );
@@ -528,10 +4302,12 @@ mod tests {
                 super::transfer_from(
                     &mut amap,
                     &mut bmap,
+                    &LocksMap::new(),
                     bob(),
                     alice(),
                     charlie(),
-                    U256::exp10(20)
+                    U256::exp10(20),
+                    0
                 ),
                 Error::InsufficientAllowance,
             );
@@ -554,10 +4330,12 @@ mod tests {
                 super::transfer_from(
                     &mut amap,
                     &mut bmap,
+                    &LocksMap::new(),
                     bob(),
                     alice(),
                     charlie(),
-                    U256::exp10(20)
+                    U256::exp10(20),
+                    0
                 ),
                 Error::InsufficientAllowance,
             );
@@ -565,7 +4343,7 @@ mod tests {
             assert_eq!(super::balance_of(&bmap, alice()), U256::exp10(42));
             assert!(super::balance_of(&bmap, bob()).is_zero());
             assert!(super::balance_of(&bmap, charlie()).is_zero());
-            assert_eq!(super::allowance(&amap, alice(), bob()), U256::exp10(19));
+            assert_eq!(super::allowance(&amap, alice(), bob(), 0), U256::exp10(19));
         }
 
         // # Test case #6.
@@ -577,10 +4355,12 @@ mod tests {
                 super::transfer_from(
                     &mut amap,
                     &mut bmap,
+                    &LocksMap::new(),
                     bob(),
                     alice(),
                     charlie(),
-                    U256::exp10(43)
+                    U256::exp10(43),
+                    0
                 ),
                 Error::InsufficientBalance,
             );
@@ -600,10 +4380,12 @@ mod tests {
                 super::transfer_from(
                     &mut amap,
                     &mut bmap,
+                    &LocksMap::new(),
                     bob(),
                     alice(),
                     charlie(),
-                    U256::exp10(42)
+                    U256::exp10(42),
+                    0
                 ),
                 true
             );
@@ -612,7 +4394,7 @@ mod tests {
             assert!(super::balance_of(&bmap, bob()).is_zero());
             assert_eq!(super::balance_of(&bmap, charlie()), U256::exp10(42));
             assert_eq!(
-                super::allowance(&amap, alice(), bob()),
+                super::allowance(&amap, alice(), bob(), 0),
                 U256::MAX - U256::exp10(42)
             );
         }
@@ -631,10 +4413,12 @@ mod tests {
                 super::transfer_from(
                     &mut amap,
                     &mut bmap,
+                    &LocksMap::new(),
                     alice(),
                     charlie(),
                     dave(),
-                    U256::exp10(42)
+                    U256::exp10(42),
+                    0
                 ),
                 true
             );
@@ -646,12 +4430,122 @@ mod tests {
                 super::balance_of(&bmap, dave()),
                 U256::exp10(42).saturating_mul(2.into())
             );
-            assert!(super::allowance(&amap, charlie().into(), alice()).is_zero());
+            assert!(super::allowance(&amap, charlie().into(), alice(), 0).is_zero());
+        }
+    }
+
+    #[test]
+    fn transfer_locked() {
+        // Initializing thread logger.
+        let _ = env_logger::try_init();
+
+        // Creating map with medium balance belonged to Alice.
+        let mut map = balances_map([(alice(), U256::exp10(42))]);
+
+        let mut locks = LocksMap::new();
+
+        // # Test case #1.
+        // Lock must expire in future.
+        {
+            assert_err!(
+                super::transfer_locked(&mut map, &mut locks, alice(), bob(), U256::one(), 5, 10),
+                Error::InvalidLock
+            );
+            assert!(super::balance_of(&map, bob()).is_zero());
+        }
+
+        // # Test case #2.
+        // Bob receives tokens he can't spend until unlock.
+        {
+            assert_ok!(
+                super::transfer_locked(
+                    &mut map,
+                    &mut locks,
+                    alice(),
+                    bob(),
+                    U256::exp10(41),
+                    20,
+                    10
+                ),
+                true
+            );
+
+            assert_eq!(super::balance_of(&map, bob()), U256::exp10(41));
+            assert_eq!(super::locked_balance_of(&locks, bob(), 10), U256::exp10(41));
+            assert_err!(
+                super::transfer(&mut map, &locks, bob(), charlie(), U256::one()),
+                Error::BalanceLocked
+            );
+        }
+
+        // # Test case #3.
+        // Noop transfers don't create locks.
+        {
+            assert_ok!(
+                super::transfer_locked(
+                    &mut map,
+                    &mut locks,
+                    alice(),
+                    charlie(),
+                    U256::zero(),
+                    20,
+                    10
+                ),
+                false
+            );
+            assert!(super::locked_balance_of(&locks, charlie(), 10).is_zero());
+        }
+
+        // # Test case #4.
+        // Receiver's locks are capped, so failure leaves balances untouched.
+        {
+            for until in 21..20 + MAX_LOCKS as u32 {
+                assert_ok!(
+                    super::transfer_locked(
+                        &mut map,
+                        &mut locks,
+                        alice(),
+                        bob(),
+                        U256::one(),
+                        until,
+                        10
+                    ),
+                    true
+                );
+            }
+
+            let before = map.clone();
+
+            assert_err!(
+                super::transfer_locked(&mut map, &mut locks, alice(), bob(), U256::one(), 100, 10),
+                Error::TooManyLocks
+            );
+            assert_eq!(map, before);
+            assert_eq!(locks[&bob()].received.len(), MAX_LOCKS);
+        }
+
+        // # Test case #5.
+        // Received locks don't take Bob's own slots, so he still locks spendable tokens.
+        {
+            assert_ok!(
+                super::transfer(&mut map, &locks, alice(), bob(), U256::one()),
+                true
+            );
+
+            assert_ok!(
+                super::lock(&map, &mut locks, bob(), U256::one(), 100, 10),
+                true
+            );
+            assert_eq!(locks[&bob()].own, vec![(100, U256::one())]);
         }
     }
 
     mod utils {
-        use super::{AllowancesMap, BalancesMap};
+        use super::{
+            AllowancesMap, BalancesMap, BlocklistSet, NonZeroU256, Signature, StateMeta,
+            VestingSchedule,
+        };
+        use ed25519_dalek::Signer;
         use gstd::ActorId;
         use primitive_types::U256;
 
@@ -660,10 +4554,24 @@ mod tests {
         ) -> AllowancesMap {
             content
                 .into_iter()
-                .map(|(k1, k2, v)| ((k1, k2), v.try_into().unwrap()))
+                .map(|(k1, k2, v)| ((k1, k2), (v.try_into().unwrap(), None)))
                 .collect()
         }
 
+        pub fn non_zero(value: U256) -> NonZeroU256 {
+            value.try_into().unwrap()
+        }
+
+        pub fn state_meta() -> StateMeta {
+            StateMeta {
+                name: "Vara Network".into(),
+                symbol: "VARA".into(),
+                decimals: 12,
+                cap: None,
+                total_supply: U256::exp10(42) + U256::exp10(24),
+            }
+        }
+
         pub fn balances_map<const N: usize>(content: [(ActorId, U256); N]) -> BalancesMap {
             content
                 .into_iter()
@@ -671,6 +4579,54 @@ mod tests {
                 .collect()
         }
 
+        pub fn blocklist_set<const N: usize>(content: [ActorId; N]) -> BlocklistSet {
+            content.into_iter().collect()
+        }
+
+        pub fn ed25519_signer(seed: u8) -> (ActorId, impl Fn(&[u8]) -> Signature) {
+            let key = ed25519_dalek::SigningKey::from_bytes(&[seed; 32]);
+
+            let signer = key.verifying_key().to_bytes().into();
+
+            (signer, move |message: &[u8]| {
+                Signature::Ed25519(key.sign(message).to_bytes())
+            })
+        }
+
+        pub fn sr25519_signer(seed: u8) -> (ActorId, impl Fn(&[u8]) -> Signature) {
+            let key = schnorrkel::MiniSecretKey::from_bytes(&[seed; 32])
+                .expect("Infallible since length is valid")
+                .expand_to_keypair(schnorrkel::ExpansionMode::Ed25519);
+
+            let signer = key.public.to_bytes().into();
+
+            (signer, move |message: &[u8]| {
+                Signature::Sr25519(
+                    key.sign_simple(super::SR25519_SIGNING_CONTEXT, message)
+                        .to_bytes(),
+                )
+            })
+        }
+
+        // Schedule starting at 100 with cliff at 200 and end at 500.
+        pub fn schedule_of(beneficiary: ActorId, total: U256) -> VestingSchedule {
+            VestingSchedule {
+                beneficiary,
+                total,
+                released: U256::zero(),
+                start: 100,
+                cliff: 100,
+                duration: 400,
+                revocable: true,
+            }
+        }
+
+        pub fn total_supply_of(balances: &BalancesMap) -> U256 {
+            balances
+                .values()
+                .fold(U256::zero(), |acc, v| acc + U256::from(*v))
+        }
+
         pub fn alice() -> ActorId {
             1u64.into()
         }
@@ -686,5 +4642,9 @@ mod tests {
         pub fn dave() -> ActorId {
             4u64.into()
         }
+
+        pub fn eve() -> ActorId {
+            5u64.into()
+        }
     }
 }
@@ -1,38 +1,168 @@
 #![allow(clippy::unused_unit)]
 
 mod internal;
-mod storage;
+pub(crate) mod storage;
 
 pub use internal::*;
 
 use self::storage::{
-    allowances::AllowancesStorage, balances::BalancesStorage, meta::MetaStorage,
+    allowances::{AllowancesStorage, SpendersStorage},
+    balances::{BalancesStorage, HoldersStorage},
+    blocklist::BlocklistStorage,
+    import::ImportStorage,
+    locks::LocksStorage,
+    meta::{Meta, MetaStorage},
+    nonces::NoncesStorage,
+    pause::PauseStorage,
+    roles::RolesStorage,
+    snapshots::SnapshotsStorage,
     total_supply::TotalSupplyStorage,
+    vesting::VestingStorage,
+    votes::VotesStorage,
+    AllowancesProvider, BalancesProvider, Storage,
 };
-use core::{cmp::Ordering, fmt::Debug};
-use gstd::{ext, format, msg, ActorId, Decode, Encode, String, TypeInfo, Vec};
+use super::{
+    context, panic, panicking,
+    roles::{self, ensure_role, Role},
+};
+use core::{cell::RefCell, marker::PhantomData};
+use gstd::{prelude::rc::Rc, ActorId, Decode, Encode, String, TypeInfo, Vec};
 use primitive_types::U256;
 use sails_macros::gservice;
 use sails_rtl::gstd::events::EventTrigger;
 
-pub struct Service<X> {
+pub struct Service<X, B = BalancesStorage, A = AllowancesStorage> {
     informer: X,
+    _stores: PhantomData<(B, A)>,
 }
 
-impl<X> Service<X> {
-    pub fn seed(name: String, symbol: String, decimals: u8) {
-        let _res = AllowancesStorage::default();
-        debug_assert!(_res.is_ok());
+impl<X, B: BalancesProvider, A: AllowancesProvider> Service<X, B, A> {
+    // Sets every storage once, so accessing unset or setting set one panics.
+    pub fn seed(name: String, symbol: String, decimals: u8, cap: Option<U256>) {
+        panicking(A::default);
+        panicking(B::default);
+
+        panicking(|| {
+            MetaStorage::set(Meta {
+                name,
+                symbol,
+                decimals,
+                cap,
+            })
+        });
+
+        panicking(BlocklistStorage::default);
+        panicking(HoldersStorage::default);
+        panicking(ImportStorage::default);
+        panicking(LocksStorage::default);
+        panicking(NoncesStorage::default);
+        panicking(PauseStorage::default);
+        panicking(SnapshotsStorage::default);
+        panicking(SpendersStorage::default);
+        panicking(TotalSupplyStorage::default);
+        panicking(VestingStorage::default);
+        panicking(VotesStorage::default);
+    }
+
+    // Token stays paused until the rest of chunks are imported with `import_state`.
+    pub fn seed_from_state(chunk: StateChunk) {
+        let StateMeta {
+            name,
+            symbol,
+            decimals,
+            cap,
+            ..
+        } = chunk.meta.clone();
+
+        Self::seed(name, symbol, decimals, cap);
+
+        // Rest of chunks must carry the same meta.
+        *ImportStorage::get_mut() = Some((0, chunk.meta.clone()));
+
+        // Nobody delegates before import completes, so no votes change here.
+        panicking(move || Self::import_chunk(chunk));
+    }
+
+    // Imported balances are written as any other ones, so votes and indexes follow them.
+    fn import_chunk(chunk: StateChunk) -> Result<(bool, Vec<VotesChange>), Error> {
+        let approvals: Vec<_> = chunk
+            .allowances
+            .iter()
+            .map(|&(owner, spender, ..)| (owner, spender))
+            .collect();
+
+        let (complete, changes) = Self::track_balances(|balances| {
+            import_state(
+                balances,
+                A::get_mut(),
+                TotalSupplyStorage::get_mut(),
+                MetaStorage::cap(),
+                ImportStorage::get_mut(),
+                chunk,
+            )
+        });
+
+        let complete = complete?;
 
-        let _res = BalancesStorage::default();
-        debug_assert!(_res.is_ok());
+        for (owner, spender) in approvals {
+            SpendersStorage::reindex(A::get(), owner, spender)
+        }
 
-        let _res = MetaStorage::set(name, symbol, decimals);
-        debug_assert!(_res.is_ok());
+        Ok((complete, changes))
+    }
+
+    // Balances are only mutated through this, so snapshots, delegated votes
+    // and holders index follow every change.
+    fn track_balances<T>(
+        f: impl FnOnce(&mut TrackedBalances<B::Store>) -> T,
+    ) -> (T, Vec<VotesChange>) {
+        let mut balances = TrackedBalances::new(
+            B::get_mut(),
+            SnapshotsStorage::get_mut(),
+            *TotalSupplyStorage::get(),
+            VotesStorage::get_mut(),
+            context::block_height(),
+        );
+
+        let res = f(&mut balances);
+
+        let (accounts, changes) = balances.finish();
+
+        for account in accounts {
+            HoldersStorage::reindex(B::get(), account)
+        }
+
+        (res, changes)
+    }
+
+    // Burns escrowed tokens once value is delivered and returns them otherwise.
+    fn settle_withdrawal(
+        account: ActorId,
+        holder: ActorId,
+        value: NonZeroU256,
+        delivered: bool,
+    ) -> Vec<VotesChange> {
+        let (_res, changes) = Self::track_balances(|balances| {
+            if delivered {
+                burn(
+                    balances,
+                    LocksStorage::get(),
+                    TotalSupplyStorage::get_mut(),
+                    holder,
+                    value.into(),
+                )
+            } else {
+                transfer(balances, LocksStorage::get(), holder, account, value.into())
+            }
+        });
+        // Sum of balances is bounded with total supply, so neither fails for escrowed tokens.
+        debug_assert_eq!(_res, Ok(true));
+
+        changes
     }
 }
 
-impl<X: EventTrigger<Event>> Service<X> {
+impl<X: EventTrigger<Event>, B: BalancesProvider, A: AllowancesProvider> Service<X, B, A> {
     pub fn deposit_event(&self, e: Event) {
         // TODO (sails): rename to `deposit_event`
         // TODO (sails): make infallible or something?
@@ -40,6 +170,46 @@ impl<X: EventTrigger<Event>> Service<X> {
             panic("Failed to deposit event");
         }
     }
+
+    fn with_balances<T>(&self, f: impl FnOnce(&mut TrackedBalances<B::Store>) -> T) -> T {
+        let (res, changes) = Self::track_balances(f);
+
+        self.deposit_votes_events(changes);
+
+        res
+    }
+
+    fn escrow_transfer(&self, from: ActorId, to: ActorId, value: NonZeroU256) -> Result<()> {
+        self.with_balances(|balances| {
+            transfer(balances, LocksStorage::get(), from, to, value.into())
+        })?;
+
+        self.deposit_event(Event::Transfer { from, to, value });
+
+        Ok(())
+    }
+
+    fn deposit_votes_events(&self, changes: Vec<VotesChange>) {
+        for (delegate, previous_votes, new_votes) in changes {
+            self.deposit_event(Event::DelegateVotesChanged {
+                delegate,
+                previous_votes,
+                new_votes,
+            })
+        }
+    }
+
+    fn deposit_transfer_events(&self, from: ActorId, transfers: &[(ActorId, U256)]) {
+        for &(to, value) in transfers {
+            if from == to {
+                continue;
+            }
+
+            if let Ok(value) = value.try_into() {
+                self.deposit_event(Event::Transfer { from, to, value })
+            }
+        }
+    }
 }
 
 // TODO (sails): consider renaming `EventTrigger` -> `Notifier`/`Informer`.
@@ -48,23 +218,116 @@ impl<X: EventTrigger<Event>> Service<X> {
 // TODO (sails): let me specify error as subset of strings (Display of my Error) -> thats common flow for us.
 // TODO (sails): fix bug with unreachable names.
 // TODO (sails): gstd::ActorId, primitive_types::H256/U256, [u8; 32], NonZeroStuff are primitives!.
+//
+// Commands perform all the checks before mutating storage, so replying with
+// `Err` leaves the state untouched, same as the failed message would.
+// Dropping expired locks may precede the checks, since it changes no observable value.
+// The `*_and_call` ones revert their changes on receiver's rejection instead.
 #[gservice]
-impl<X: EventTrigger<Event>> Service<X> {
+impl<X: EventTrigger<Event>, B: BalancesProvider, A: AllowancesProvider> Service<X, B, A> {
     // TODO (sails): hide this into macro.
     pub fn new(informer: X) -> Self {
-        Self { informer }
+        Self {
+            informer,
+            _stores: PhantomData,
+        }
     }
 
     pub fn allowance(&self, owner: ActorId, spender: ActorId) -> U256 {
-        allowance(AllowancesStorage::get(), owner, spender)
+        allowance(A::get(), owner, spender, context::block_height())
+    }
+
+    pub fn allowances_of(&self, owner: ActorId, offset: u32, limit: u32) -> Vec<(ActorId, U256)> {
+        allowances_of(
+            A::get(),
+            owner,
+            SpendersStorage::spenders_of(owner),
+            offset,
+            limit,
+            context::block_height(),
+        )
+    }
+
+    pub fn approve(&mut self, spender: ActorId, value: U256) -> Result<bool, Error> {
+        let owner = context::source();
+
+        ensure_unpaused(Operation::Approve)?;
+
+        let mutated = approve(A::get_mut(), owner, spender, value);
+
+        if mutated {
+            SpendersStorage::reindex(A::get(), owner, spender);
+
+            self.deposit_event(Event::Approval {
+                owner,
+                spender,
+                value,
+            })
+        }
+
+        Ok(mutated)
+    }
+
+    pub async fn approve_and_call(
+        &mut self,
+        spender: ActorId,
+        value: U256,
+        data: Vec<u8>,
+    ) -> Result<bool, Error> {
+        let owner = context::source();
+
+        let (previous, expires_at) =
+            active_allowance(A::get(), owner, spender, context::block_height()).unwrap_or_default();
+
+        if !self.approve(spender, value)? {
+            return Ok(false);
+        }
+
+        if notify_receiver(spender, ON_APPROVAL_RECEIVED, (owner, value, data)).await {
+            return Ok(true);
+        }
+
+        // Allowance spent or approved anew meanwhile is newer one, so it's kept.
+        let current =
+            active_allowance(A::get(), owner, spender, context::block_height()).unwrap_or_default();
+
+        if current == (value, None)
+            && set_allowance(A::get_mut(), owner, spender, previous, expires_at)
+        {
+            SpendersStorage::reindex(A::get(), owner, spender);
+
+            self.deposit_event(Event::Approval {
+                owner,
+                spender,
+                value: previous,
+            })
+        }
+
+        Err(Error::ReceiverRejected)
     }
 
-    pub fn approve(&mut self, spender: ActorId, value: U256) -> bool {
-        let owner = msg::source();
+    pub fn approve_with_expiry(
+        &mut self,
+        spender: ActorId,
+        value: U256,
+        expires_at: u32,
+    ) -> Result<bool, Error> {
+        let owner = context::source();
+
+        ensure_unpaused(Operation::Approve)?;
 
-        let mutated = approve(AllowancesStorage::get_mut(), owner, spender, value);
+        let mutated = approve_with_expiry(
+            A::get_mut(),
+            owner,
+            spender,
+            value,
+            expires_at,
+            context::block_height(),
+        )?;
 
         if mutated {
+            SpendersStorage::reindex(A::get(), owner, spender);
+
             self.deposit_event(Event::Approval {
                 owner,
                 spender,
@@ -72,34 +335,737 @@ impl<X: EventTrigger<Event>> Service<X> {
             })
         }
 
-        mutated
+        Ok(mutated)
     }
 
     pub fn balance_of(&self, owner: ActorId) -> U256 {
-        balance_of(BalancesStorage::get(), owner)
+        balance_of(B::get(), owner)
+    }
+
+    pub fn balance_of_at(&self, owner: ActorId, id: u64) -> Result<U256, Error> {
+        balance_of_at(SnapshotsStorage::get(), B::get(), owner, id)
+    }
+
+    pub fn batch_transfer(&mut self, transfers: Vec<(ActorId, U256)>) -> Result<bool, Error> {
+        let from = context::source();
+
+        ensure_unpaused(Operation::Transfer)?;
+        ensure_not_frozen(BlocklistStorage::get(), from)?;
+
+        for &(to, _) in &transfers {
+            ensure_not_frozen(BlocklistStorage::get(), to)?;
+        }
+
+        unlock_expired_of(from);
+
+        let mutated = self.with_balances(|balances| {
+            batch_transfer(balances, LocksStorage::get(), from, &transfers)
+        })?;
+
+        if mutated {
+            self.deposit_transfer_events(from, &transfers)
+        }
+
+        Ok(mutated)
+    }
+
+    pub fn batch_transfer_from(
+        &mut self,
+        from: ActorId,
+        transfers: Vec<(ActorId, U256)>,
+    ) -> Result<bool, Error> {
+        let spender = context::source();
+
+        ensure_unpaused(Operation::Transfer)?;
+        ensure_not_frozen(BlocklistStorage::get(), from)?;
+
+        for &(to, _) in &transfers {
+            ensure_not_frozen(BlocklistStorage::get(), to)?;
+        }
+
+        unlock_expired_of(from);
+
+        let mutated = self.with_balances(|balances| {
+            batch_transfer_from(
+                A::get_mut(),
+                balances,
+                LocksStorage::get(),
+                spender,
+                from,
+                &transfers,
+                context::block_height(),
+            )
+        })?;
+
+        if mutated {
+            SpendersStorage::reindex(A::get(), from, spender);
+
+            self.deposit_transfer_events(from, &transfers)
+        }
+
+        Ok(mutated)
+    }
+
+    // Holders burn their own tokens, while burners are privileged to burn from any account.
+    pub fn burn(&mut self, from: ActorId, value: U256) -> Result<bool, Error> {
+        let sender = context::source();
+
+        if sender == from {
+            ensure_not_frozen(BlocklistStorage::get(), from)?;
+        } else {
+            ensure_role(RolesStorage::get(), sender, Role::Burner)?;
+        }
+
+        ensure_imported()?;
+
+        unlock_expired_of(from);
+
+        let mutated = self.with_balances(|balances| {
+            burn(
+                balances,
+                LocksStorage::get(),
+                TotalSupplyStorage::get_mut(),
+                from,
+                value,
+            )
+        })?;
+
+        if mutated {
+            let value = value
+                .try_into()
+                .expect("Infallible since `burn` executed successfully");
+
+            self.deposit_event(Event::Transfer {
+                from,
+                to: ActorId::zero(),
+                value,
+            })
+        }
+
+        Ok(mutated)
+    }
+
+    pub fn burn_from(&mut self, from: ActorId, value: U256) -> Result<bool, Error> {
+        let spender = context::source();
+
+        ensure_imported()?;
+        ensure_not_frozen(BlocklistStorage::get(), from)?;
+
+        unlock_expired_of(from);
+
+        let mutated = self.with_balances(|balances| {
+            burn_from(
+                A::get_mut(),
+                balances,
+                LocksStorage::get(),
+                TotalSupplyStorage::get_mut(),
+                spender,
+                from,
+                value,
+                context::block_height(),
+            )
+        })?;
+
+        if mutated {
+            SpendersStorage::reindex(A::get(), from, spender);
+
+            let value = value
+                .try_into()
+                .expect("Infallible since `burn_from` executed successfully");
+
+            self.deposit_event(Event::Transfer {
+                from,
+                to: ActorId::zero(),
+                value,
+            })
+        }
+
+        Ok(mutated)
+    }
+
+    pub fn cap(&self) -> Option<U256> {
+        MetaStorage::cap()
+    }
+
+    pub fn create_vesting(
+        &mut self,
+        beneficiary: ActorId,
+        total: U256,
+        start: u64,
+        cliff: u64,
+        duration: u64,
+        revocable: bool,
+    ) -> Result<bool, Error> {
+        let sender = context::source();
+        let holder = context::program_id();
+
+        ensure_role(RolesStorage::get(), sender, Role::Admin)?;
+
+        unlock_expired_of(sender);
+
+        let schedule = VestingSchedule {
+            beneficiary,
+            total,
+            released: U256::zero(),
+            start,
+            cliff,
+            duration,
+            revocable,
+        };
+
+        let mutated = self.with_balances(|balances| {
+            create_vesting(
+                VestingStorage::get_mut(),
+                balances,
+                LocksStorage::get(),
+                holder,
+                sender,
+                schedule,
+            )
+        })?;
+
+        if mutated {
+            let total = total
+                .try_into()
+                .expect("Infallible since `create_vesting` executed successfully");
+
+            self.deposit_event(Event::Transfer {
+                from: sender,
+                to: holder,
+                value: total,
+            });
+            self.deposit_event(Event::VestingCreated { beneficiary, total });
+        }
+
+        Ok(mutated)
     }
 
     pub fn decimals(&self) -> u8 {
         MetaStorage::decimals()
     }
 
+    pub fn decrease_allowance(&mut self, spender: ActorId, delta: U256) -> Result<bool, Error> {
+        let owner = context::source();
+
+        ensure_unpaused(Operation::Approve)?;
+
+        let now = context::block_height();
+
+        let mutated = decrease_allowance(A::get_mut(), owner, spender, delta, now)?;
+
+        if mutated {
+            SpendersStorage::reindex(A::get(), owner, spender);
+
+            self.deposit_event(Event::Approval {
+                owner,
+                spender,
+                value: allowance(A::get(), owner, spender, now),
+            })
+        }
+
+        Ok(mutated)
+    }
+
+    // Waits for import to complete, so delegated accounts' balances are final.
+    pub fn delegate(&mut self, delegatee: ActorId) -> Result<bool, Error> {
+        let delegator = context::source();
+
+        ensure_imported()?;
+
+        let Some((from_delegate, changes)) = delegate(
+            VotesStorage::get_mut(),
+            B::get(),
+            delegator,
+            delegatee,
+            context::block_height(),
+        ) else {
+            return Ok(false);
+        };
+
+        self.deposit_event(Event::DelegateChanged {
+            delegator,
+            from_delegate,
+            to_delegate: delegatee,
+        });
+        self.deposit_votes_events(changes);
+
+        Ok(true)
+    }
+
+    pub fn delegates(&self, account: ActorId) -> ActorId {
+        delegates(VotesStorage::get(), account)
+    }
+
+    pub fn deposit(&mut self) -> Result<bool, Error> {
+        let account = context::source();
+        let amount = context::value();
+
+        let res = ensure_unpaused(Operation::Mint)
+            .and_then(|()| ensure_not_frozen(BlocklistStorage::get(), account))
+            .and_then(|()| {
+                self.with_balances(|balances| {
+                    mint(
+                        balances,
+                        TotalSupplyStorage::get_mut(),
+                        MetaStorage::cap(),
+                        account,
+                        amount.into(),
+                    )
+                })
+            });
+
+        let mutated = match res {
+            Ok(mutated) => mutated,
+            Err(err) => {
+                // Attached value stays on program's balance unless sent back.
+                // Panicking here returns it to the sender as well.
+                if amount != 0 && !context::send_value(account, amount) {
+                    panic(err)
+                }
+
+                return Err(err);
+            }
+        };
+
+        if mutated {
+            let value = U256::from(amount)
+                .try_into()
+                .expect("Infallible since `mint` executed successfully");
+
+            self.deposit_event(Event::Transfer {
+                from: ActorId::zero(),
+                to: account,
+                value,
+            });
+            self.deposit_event(Event::Deposit { account, value });
+        }
+
+        Ok(mutated)
+    }
+
+    pub fn domain_separator(&self) -> [u8; 32] {
+        domain_separator(&MetaStorage::get().name, context::program_id())
+    }
+
+    pub fn export_state(&self, offset: u32, limit: u32) -> Result<StateChunk, Error> {
+        ensure_role(RolesStorage::get(), context::source(), Role::Admin)?;
+
+        let meta = MetaStorage::get();
+
+        let meta = StateMeta {
+            name: meta.name.clone(),
+            symbol: meta.symbol.clone(),
+            decimals: meta.decimals,
+            cap: meta.cap,
+            total_supply: *TotalSupplyStorage::get(),
+        };
+
+        Ok(export_state(
+            B::get(),
+            A::get(),
+            HoldersStorage::holders(),
+            SpendersStorage::approvals(),
+            meta,
+            offset,
+            limit,
+        ))
+    }
+
+    pub fn freeze(&mut self, account: ActorId) -> Result<bool, Error> {
+        let sender = context::source();
+
+        ensure_role(RolesStorage::get(), sender, Role::Admin)?;
+
+        let mutated = freeze(BlocklistStorage::get_mut(), account);
+
+        if mutated {
+            self.deposit_event(Event::Frozen { account })
+        }
+
+        Ok(mutated)
+    }
+
+    pub fn get_past_votes(&self, account: ActorId, block: u32) -> Result<U256, Error> {
+        get_past_votes(VotesStorage::get(), account, block, context::block_height())
+    }
+
+    pub fn get_votes(&self, account: ActorId) -> U256 {
+        get_votes(VotesStorage::get(), account)
+    }
+
+    pub fn holders(&self, offset: u32, limit: u32) -> Vec<(ActorId, U256)> {
+        holders(B::get(), HoldersStorage::holders(), offset, limit)
+    }
+
+    pub fn holders_count(&self) -> u32 {
+        holders_count(B::get())
+    }
+
+    pub fn import_state(&mut self, chunk: StateChunk) -> Result<bool, Error> {
+        let sender = context::source();
+
+        ensure_role(RolesStorage::get(), sender, Role::Admin)?;
+
+        let (complete, changes) = Self::import_chunk(chunk)?;
+
+        self.deposit_votes_events(changes);
+
+        if complete && !PauseStorage::get().global {
+            self.deposit_event(Event::Unpaused { operation: None })
+        }
+
+        Ok(complete)
+    }
+
+    pub fn increase_allowance(&mut self, spender: ActorId, delta: U256) -> Result<bool, Error> {
+        let owner = context::source();
+
+        ensure_unpaused(Operation::Approve)?;
+
+        let now = context::block_height();
+
+        let mutated = increase_allowance(A::get_mut(), owner, spender, delta, now)?;
+
+        if mutated {
+            SpendersStorage::reindex(A::get(), owner, spender);
+
+            self.deposit_event(Event::Approval {
+                owner,
+                spender,
+                value: allowance(A::get(), owner, spender, now),
+            })
+        }
+
+        Ok(mutated)
+    }
+
+    pub fn is_frozen(&self, account: ActorId) -> bool {
+        is_frozen(BlocklistStorage::get(), account)
+    }
+
+    pub fn is_paused(&self, operation: Operation) -> bool {
+        is_paused(operation)
+    }
+
+    pub fn lock(&mut self, value: U256, until: u32) -> Result<bool, Error> {
+        let account = context::source();
+
+        ensure_not_frozen(BlocklistStorage::get(), account)?;
+
+        unlock_expired_of(account);
+
+        let mutated = lock(
+            B::get(),
+            LocksStorage::get_mut(),
+            account,
+            value,
+            until,
+            context::block_height(),
+        )?;
+
+        if mutated {
+            let value = value
+                .try_into()
+                .expect("Infallible since `lock` executed successfully");
+
+            self.deposit_event(Event::Locked {
+                account,
+                value,
+                until,
+            })
+        }
+
+        Ok(mutated)
+    }
+
+    pub fn locked_balance_of(&self, account: ActorId) -> U256 {
+        locked_balance_of(LocksStorage::get(), account, context::block_height())
+    }
+
+    pub fn mint(&mut self, to: ActorId, value: U256) -> Result<bool, Error> {
+        let sender = context::source();
+
+        ensure_role(RolesStorage::get(), sender, Role::Minter)?;
+        ensure_unpaused(Operation::Mint)?;
+        ensure_not_frozen(BlocklistStorage::get(), to)?;
+
+        let mutated = self.with_balances(|balances| {
+            mint(
+                balances,
+                TotalSupplyStorage::get_mut(),
+                MetaStorage::cap(),
+                to,
+                value,
+            )
+        })?;
+
+        if mutated {
+            let value = value
+                .try_into()
+                .expect("Infallible since `mint` executed successfully");
+
+            self.deposit_event(Event::Transfer {
+                from: ActorId::zero(),
+                to,
+                value,
+            })
+        }
+
+        Ok(mutated)
+    }
+
     // TODO (sails): allow using references.
     pub fn name(&self) -> String {
         MetaStorage::name()
     }
 
+    pub fn nonces(&self, owner: ActorId) -> u64 {
+        nonce_of(NoncesStorage::get(), owner)
+    }
+
+    pub fn pause(&mut self) -> Result<bool, Error> {
+        let sender = context::source();
+
+        ensure_role(RolesStorage::get(), sender, Role::Pauser)?;
+
+        let pause = PauseStorage::get_mut();
+
+        let mutated = !pause.global;
+
+        if mutated {
+            pause.global = true;
+
+            self.deposit_event(Event::Paused { operation: None })
+        }
+
+        Ok(mutated)
+    }
+
+    pub fn pause_operation(&mut self, operation: Operation) -> Result<bool, Error> {
+        let sender = context::source();
+
+        ensure_role(RolesStorage::get(), sender, Role::Pauser)?;
+
+        let mutated = PauseStorage::get_mut().operations.insert(operation);
+
+        if mutated {
+            self.deposit_event(Event::Paused {
+                operation: Some(operation),
+            })
+        }
+
+        Ok(mutated)
+    }
+
+    pub fn paused(&self) -> bool {
+        PauseStorage::get().global || ImportStorage::get().is_some()
+    }
+
+    pub fn permit(
+        &mut self,
+        owner: ActorId,
+        spender: ActorId,
+        value: U256,
+        deadline: u64,
+        signature: Signature,
+    ) -> Result<bool, Error> {
+        ensure_unpaused(Operation::Approve)?;
+
+        let message = Permit {
+            domain_separator: self.domain_separator(),
+            owner,
+            spender,
+            value,
+            nonce: self.nonces(owner),
+            deadline,
+        };
+
+        let mutated = permit(
+            A::get_mut(),
+            NoncesStorage::get_mut(),
+            &message,
+            &signature,
+            context::block_timestamp(),
+        )?;
+
+        if mutated {
+            SpendersStorage::reindex(A::get(), owner, spender);
+
+            self.deposit_event(Event::Approval {
+                owner,
+                spender,
+                value,
+            })
+        }
+
+        Ok(mutated)
+    }
+
+    pub fn releasable_amount(&self, beneficiary: ActorId, at: u64) -> U256 {
+        releasable_amount(VestingStorage::get(), beneficiary, at)
+    }
+
+    pub fn release(&mut self) -> Result<bool, Error> {
+        let beneficiary = context::source();
+        let holder = context::program_id();
+
+        ensure_unpaused(Operation::Transfer)?;
+        ensure_not_frozen(BlocklistStorage::get(), beneficiary)?;
+
+        let value = self.with_balances(|balances| {
+            release(
+                VestingStorage::get_mut(),
+                balances,
+                LocksStorage::get(),
+                holder,
+                beneficiary,
+                context::block_timestamp(),
+            )
+        })?;
+
+        let Ok(value) = value.try_into() else {
+            return Ok(false);
+        };
+
+        self.deposit_event(Event::Transfer {
+            from: holder,
+            to: beneficiary,
+            value,
+        });
+        self.deposit_event(Event::VestingReleased { beneficiary, value });
+
+        Ok(true)
+    }
+
+    pub fn revoke_all(&mut self) -> Result<bool, Error> {
+        let owner = context::source();
+
+        ensure_unpaused(Operation::Approve)?;
+
+        let spenders: Vec<_> = SpendersStorage::spenders_of(owner).collect();
+
+        let revoked = revoke_all(A::get_mut(), owner, spenders);
+
+        for &spender in &revoked {
+            SpendersStorage::reindex(A::get(), owner, spender);
+
+            self.deposit_event(Event::Approval {
+                owner,
+                spender,
+                value: U256::zero(),
+            })
+        }
+
+        Ok(!revoked.is_empty())
+    }
+
+    pub fn revoke_vesting(&mut self, beneficiary: ActorId) -> Result<bool, Error> {
+        let sender = context::source();
+        let holder = context::program_id();
+
+        ensure_role(RolesStorage::get(), sender, Role::Admin)?;
+
+        let refund = self.with_balances(|balances| {
+            revoke_vesting(
+                VestingStorage::get_mut(),
+                balances,
+                LocksStorage::get(),
+                holder,
+                beneficiary,
+                sender,
+                context::block_timestamp(),
+            )
+        })?;
+
+        if let Ok(value) = refund.try_into() {
+            self.deposit_event(Event::Transfer {
+                from: holder,
+                to: sender,
+                value,
+            })
+        }
+
+        self.deposit_event(Event::VestingRevoked {
+            beneficiary,
+            refund,
+        });
+
+        Ok(true)
+    }
+
+    pub fn seize(&mut self, from: ActorId, to: ActorId) -> Result<bool, Error> {
+        let sender = context::source();
+
+        ensure_role(RolesStorage::get(), sender, Role::Admin)?;
+
+        let value = self.with_balances(|balances| {
+            seize(
+                BlocklistStorage::get(),
+                balances,
+                LocksStorage::get_mut(),
+                from,
+                to,
+            )
+        })?;
+
+        let Ok(value) = value.try_into() else {
+            return Ok(false);
+        };
+
+        self.deposit_event(Event::Transfer { from, to, value });
+        self.deposit_event(Event::Seized { from, to, value });
+
+        Ok(true)
+    }
+
+    pub fn snapshot(&mut self) -> Result<u64, Error> {
+        let sender = context::source();
+
+        ensure_role(RolesStorage::get(), sender, Role::Admin)?;
+
+        let id = snapshot(SnapshotsStorage::get_mut())?;
+
+        self.deposit_event(Event::Snapshot { id });
+
+        Ok(id)
+    }
+
+    pub fn spenders_of(&self, owner: ActorId) -> Vec<ActorId> {
+        spenders_of(
+            A::get(),
+            owner,
+            SpendersStorage::spenders_of(owner),
+            context::block_height(),
+        )
+    }
+
     pub fn symbol(&self) -> String {
         MetaStorage::symbol()
     }
 
     pub fn total_supply(&self) -> U256 {
-        TotalSupplyStorage::get()
+        *TotalSupplyStorage::get()
     }
 
-    pub fn transfer(&mut self, to: ActorId, value: U256) -> bool {
-        let from = msg::source();
+    pub fn total_supply_at(&self, id: u64) -> Result<U256, Error> {
+        total_supply_at(SnapshotsStorage::get(), *TotalSupplyStorage::get(), id)
+    }
+
+    pub fn top_holders(&self, n: u32) -> Vec<(ActorId, U256)> {
+        top_holders(B::get(), n)
+    }
+
+    pub fn transfer(&mut self, to: ActorId, value: U256) -> Result<bool, Error> {
+        let from = context::source();
 
-        let mutated = panicking(move || transfer(BalancesStorage::get_mut(), from, to, value));
+        ensure_unpaused(Operation::Transfer)?;
+        ensure_not_frozen(BlocklistStorage::get(), from)?;
+        ensure_not_frozen(BlocklistStorage::get(), to)?;
+
+        unlock_expired_of(from);
+
+        let mutated = self
+            .with_balances(|balances| transfer(balances, LocksStorage::get(), from, to, value))?;
 
         if mutated {
             let value = value
@@ -109,25 +1075,40 @@ impl<X: EventTrigger<Event>> Service<X> {
             self.deposit_event(Event::Transfer { from, to, value })
         }
 
-        mutated
+        Ok(mutated)
     }
 
     // TODO (breathx): rename me once bug in sails fixed.
-    pub fn from_transfer(&mut self, from: ActorId, to: ActorId, value: U256) -> bool {
-        let spender = msg::source();
+    pub fn from_transfer(
+        &mut self,
+        from: ActorId,
+        to: ActorId,
+        value: U256,
+    ) -> Result<bool, Error> {
+        let spender = context::source();
+
+        ensure_unpaused(Operation::Transfer)?;
+        ensure_not_frozen(BlocklistStorage::get(), from)?;
+        ensure_not_frozen(BlocklistStorage::get(), to)?;
 
-        let mutated = panicking(move || {
+        unlock_expired_of(from);
+
+        let mutated = self.with_balances(|balances| {
             transfer_from(
-                AllowancesStorage::get_mut(),
-                BalancesStorage::get_mut(),
+                A::get_mut(),
+                balances,
+                LocksStorage::get(),
                 spender,
                 from,
                 to,
                 value,
+                context::block_height(),
             )
-        });
+        })?;
 
         if mutated {
+            SpendersStorage::reindex(A::get(), from, spender);
+
             let value = value
                 .try_into()
                 .expect("Infallible since `transfer_from` executed successfully");
@@ -135,39 +1116,249 @@ impl<X: EventTrigger<Event>> Service<X> {
             self.deposit_event(Event::Transfer { from, to, value })
         }
 
-        mutated
+        Ok(mutated)
     }
 
-    // TODO (breathx): delete me once multi services are implemented.
-    pub fn set_balance(&mut self, new_balance: U256) -> bool {
-        let owner = msg::source();
+    // Tokens are escrowed on program's account until receiver accepts them,
+    // so rejected transfer is never committed. Receivers see it documented in README.
+    pub async fn transfer_and_call(
+        &mut self,
+        to: ActorId,
+        value: U256,
+        data: Vec<u8>,
+    ) -> Result<bool, Error> {
+        let from = context::source();
+        let holder = context::program_id();
 
-        let balance = balance_of(BalancesStorage::get(), owner);
+        ensure_unpaused(Operation::Transfer)?;
+        ensure_not_frozen(BlocklistStorage::get(), from)?;
+        ensure_not_frozen(BlocklistStorage::get(), to)?;
 
-        let new_total_supply = match balance.cmp(&new_balance) {
-            Ordering::Greater => TotalSupplyStorage::get().saturating_sub(balance - new_balance),
-            Ordering::Less => TotalSupplyStorage::get().saturating_add(new_balance - balance),
-            Ordering::Equal => return false,
+        let Ok(value) = NonZeroU256::try_from(value) else {
+            return Ok(false);
         };
 
-        let non_zero_new_balance = new_balance
-            .try_into()
-            .expect("Infallible since NonZero b/c new_balance != balance");
+        unlock_expired_of(from);
+
+        self.escrow_transfer(from, holder, value)?;
 
-        BalancesStorage::get_mut().insert(owner, non_zero_new_balance);
-        *TotalSupplyStorage::get_mut() = new_total_supply;
+        let accepted =
+            notify_receiver(to, ON_TOKEN_RECEIVED, (from, U256::from(value), data)).await;
 
-        true
+        // Sum of balances is bounded with total supply, so crediting escrow never fails.
+        let _res = self.escrow_transfer(holder, if accepted { to } else { from }, value);
+        debug_assert_eq!(_res, Ok(()));
+
+        if accepted {
+            Ok(true)
+        } else {
+            Err(Error::ReceiverRejected)
+        }
+    }
+
+    pub fn transfer_locked(
+        &mut self,
+        to: ActorId,
+        value: U256,
+        unlock_at: u32,
+    ) -> Result<bool, Error> {
+        let from = context::source();
+
+        ensure_unpaused(Operation::Transfer)?;
+        ensure_not_frozen(BlocklistStorage::get(), from)?;
+        ensure_not_frozen(BlocklistStorage::get(), to)?;
+
+        unlock_expired_of(from);
+        unlock_expired_of(to);
+
+        let mutated = self.with_balances(|balances| {
+            transfer_locked(
+                balances,
+                LocksStorage::get_mut(),
+                from,
+                to,
+                value,
+                unlock_at,
+                context::block_height(),
+            )
+        })?;
+
+        if mutated {
+            let value = value
+                .try_into()
+                .expect("Infallible since `transfer_locked` executed successfully");
+
+            self.deposit_event(Event::Transfer { from, to, value });
+            self.deposit_event(Event::Locked {
+                account: to,
+                value,
+                until: unlock_at,
+            });
+        }
+
+        Ok(mutated)
+    }
+
+    pub fn unfreeze(&mut self, account: ActorId) -> Result<bool, Error> {
+        let sender = context::source();
+
+        ensure_role(RolesStorage::get(), sender, Role::Admin)?;
+
+        let mutated = unfreeze(BlocklistStorage::get_mut(), account);
+
+        if mutated {
+            self.deposit_event(Event::Unfrozen { account })
+        }
+
+        Ok(mutated)
+    }
+
+    pub fn unpause(&mut self) -> Result<bool, Error> {
+        let sender = context::source();
+
+        ensure_role(RolesStorage::get(), sender, Role::Pauser)?;
+
+        let pause = PauseStorage::get_mut();
+
+        let mutated = pause.global;
+
+        if mutated {
+            pause.global = false;
+
+            self.deposit_event(Event::Unpaused { operation: None })
+        }
+
+        Ok(mutated)
+    }
+
+    pub fn unpause_operation(&mut self, operation: Operation) -> Result<bool, Error> {
+        let sender = context::source();
+
+        ensure_role(RolesStorage::get(), sender, Role::Pauser)?;
+
+        let mutated = PauseStorage::get_mut().operations.remove(&operation);
+
+        if mutated {
+            self.deposit_event(Event::Unpaused {
+                operation: Some(operation),
+            })
+        }
+
+        Ok(mutated)
+    }
+
+    pub fn vested_amount(&self, beneficiary: ActorId, at: u64) -> U256 {
+        vested_amount(VestingStorage::get(), beneficiary, at)
+    }
+
+    pub fn vesting_schedule(&self, beneficiary: ActorId) -> Option<VestingSchedule> {
+        VestingStorage::get().get(&beneficiary).copied()
+    }
+
+    // Tokens are escrowed on program's account until value is delivered,
+    // so they're burned on success and returned to the account otherwise.
+    pub async fn withdraw(&mut self, value: U256) -> Result<bool, Error> {
+        let account = context::source();
+        let holder = context::program_id();
+
+        ensure_imported()?;
+        ensure_not_frozen(BlocklistStorage::get(), account)?;
+
+        let amount = u128::try_from(value).map_err(|_| Error::NumericOverflow)?;
+
+        let Ok(value) = NonZeroU256::try_from(value) else {
+            return Ok(false);
+        };
+
+        unlock_expired_of(account);
+
+        self.escrow_transfer(account, holder, value)?;
+
+        // Escrow is settled by reply hook, so it's neither kept nor double spent
+        // if this message is evicted before the reply arrives.
+        let settled = Rc::new(RefCell::new(None));
+        let hook_settled = settled.clone();
+
+        let sent = context::send_with_reply_hook(account, Vec::new(), amount, move |delivered| {
+            let changes = <Service<(), B, A>>::settle_withdrawal(account, holder, value, delivered);
+            *hook_settled.borrow_mut() = Some((delivered, changes));
+        })
+        .await;
+
+        if !sent {
+            // Sum of balances is bounded with total supply, so crediting escrow never fails.
+            let _res = self.escrow_transfer(holder, account, value);
+            debug_assert_eq!(_res, Ok(()));
+
+            return Err(Error::ValueSendFailed);
+        }
+
+        let Some((delivered, changes)) = settled.take() else {
+            unreachable!("Infallible since reply hook runs before awaiting message; qed");
+        };
+
+        self.deposit_votes_events(changes);
+
+        if !delivered {
+            self.deposit_event(Event::Transfer {
+                from: holder,
+                to: account,
+                value,
+            });
+
+            return Err(Error::ValueSendFailed);
+        }
+
+        self.deposit_event(Event::Transfer {
+            from: holder,
+            to: ActorId::zero(),
+            value,
+        });
+        self.deposit_event(Event::Withdrawal { account, value });
+
+        Ok(true)
     }
 }
 
-fn panicking<T, F: FnOnce() -> Result<T, Error>>(f: F) -> T {
-    match f() {
-        Ok(v) => v,
-        Err(e) => panic(e),
+impl From<roles::Unauthorized> for Error {
+    fn from(_: roles::Unauthorized) -> Self {
+        Self::Unauthorized
     }
 }
 
-fn panic(err: impl Debug) -> ! {
-    ext::panic(&format!("{err:?}"))
+// Chunks are checked against supply and balances imported so far,
+// so neither supply nor delegations change until import completes.
+fn ensure_imported() -> Result<()> {
+    ImportStorage::get()
+        .is_none()
+        .then_some(())
+        .ok_or(Error::Paused)
+}
+
+fn ensure_unpaused(operation: Operation) -> Result<()> {
+    (!is_paused(operation)).then_some(()).ok_or(Error::Paused)
+}
+
+// Pending import keeps token paused, independently of pauser's flags.
+fn is_paused(operation: Operation) -> bool {
+    PauseStorage::is_paused(operation) || ImportStorage::get().is_some()
+}
+
+// Must precede spending from the account, so only active locks are respected.
+fn unlock_expired_of(account: ActorId) {
+    unlock_expired(LocksStorage::get_mut(), account, context::block_height())
+}
+
+// Failed sends, error replies and undecodable replies are all rejections.
+async fn notify_receiver(receiver: ActorId, method: &str, args: impl Encode) -> bool {
+    let payload = (RECEIVER_SERVICE, method, args).encode();
+
+    let Some(reply) = context::send_for_reply(receiver, payload, 0).await else {
+        return false;
+    };
+
+    matches!(
+        <(String, String, bool)>::decode(&mut reply.as_slice()),
+        Ok((service, name, accepted)) if accepted && service == RECEIVER_SERVICE && name == method
+    )
 }
@@ -1,206 +1,407 @@
-// TODO (sails): impl such macro
+use super::{
+    AllowancesMap, AllowancesStore, BalancesMap, BalancesStore, BlocklistSet, LocksMap, NoncesMap,
+    Operation, PendingImport, Snapshots, VestingMap, Votes,
+};
+
+use crate::services::panicking;
+use core::fmt;
+
+// Returned back on attempt to set already set storage.
+#[derive(Clone, PartialEq, Eq)]
+pub struct AlreadySet<T>(pub T);
+
+// Value isn't printed, so any storage's error could be reported.
+impl<T> fmt::Debug for AlreadySet<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("AlreadySet")
+    }
+}
 
-use super::{AllowancesMap, BalancesMap};
+// Returned on attempt to access storage before it's set.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct NotSet;
 
-pub mod balances {
-    use super::*;
+// Singleton storage, which must be set once (e.g. with `default_value`) before access.
+pub trait Storage {
+    type Value: 'static;
 
-    pub struct BalancesStorage(());
+    // Every call returns the same `static mut` (or leaked box natively).
+    //
+    // # Safety
+    //
+    // Caller mustn't use returned reference along with any other one into the slot.
+    // Accessors below take the slot once per call, so their callers in turn mustn't
+    // keep reference from `get_mut` across another access to the same storage.
+    unsafe fn slot() -> &'static mut Option<Self::Value>;
 
-    static mut INSTANCE: Option<BalancesMap> = None;
+    fn default_value() -> Self::Value;
 
-    impl BalancesStorage {
-        pub fn is_set() -> bool {
-            unsafe { INSTANCE.is_some() }
-        }
+    fn is_set() -> bool {
+        // SAFETY: reference is dropped before return.
+        unsafe { Self::slot() }.is_some()
+    }
 
-        pub fn set(value: BalancesMap) -> Result<(), BalancesMap> {
-            if Self::is_set() {
-                Err(value)
-            } else {
-                unsafe { INSTANCE = Some(value) }
-                Ok(())
-            }
-        }
+    fn set(value: Self::Value) -> Result<(), AlreadySet<Self::Value>> {
+        // SAFETY: reference is dropped before return.
+        let slot = unsafe { Self::slot() };
 
-        pub fn with_capacity(capacity: usize) -> Result<(), BalancesMap> {
-            Self::set(BalancesMap::with_capacity(capacity))
+        if slot.is_some() {
+            return Err(AlreadySet(value));
         }
 
-        pub fn default() -> Result<(), BalancesMap> {
-            Self::with_capacity(u16::MAX as usize)
-        }
+        *slot = Some(value);
+
+        Ok(())
+    }
+
+    fn default() -> Result<(), AlreadySet<Self::Value>> {
+        Self::set(Self::default_value())
+    }
+
+    fn try_get() -> Result<&'static Self::Value, NotSet> {
+        // SAFETY: caller upholds slot's invariant for the returned reference.
+        unsafe { Self::slot() }.as_ref().ok_or(NotSet)
+    }
+
+    fn try_get_mut() -> Result<&'static mut Self::Value, NotSet> {
+        // SAFETY: caller upholds slot's invariant for the returned reference.
+        unsafe { Self::slot() }.as_mut().ok_or(NotSet)
+    }
+
+    // Panics if not set, since storages are set on program's init.
+    fn get() -> &'static Self::Value {
+        panicking(Self::try_get)
+    }
+
+    fn get_mut() -> &'static mut Self::Value {
+        panicking(Self::try_get_mut)
+    }
+}
+
+// Storage of any balances store, so service could be generic over it.
+// Providers are types only, so reply hooks could capture them.
+pub trait BalancesProvider: 'static {
+    type Store: BalancesStore + 'static;
+
+    fn default() -> Result<(), AlreadySet<Self::Store>>;
+
+    fn get() -> &'static Self::Store;
+
+    fn get_mut() -> &'static mut Self::Store;
+}
+
+impl<S: Storage + 'static> BalancesProvider for S
+where
+    S::Value: BalancesStore,
+{
+    type Store = S::Value;
+
+    fn default() -> Result<(), AlreadySet<Self::Store>> {
+        <S as Storage>::default()
+    }
+
+    fn get() -> &'static Self::Store {
+        <S as Storage>::get()
+    }
+
+    fn get_mut() -> &'static mut Self::Store {
+        <S as Storage>::get_mut()
+    }
+}
+
+// Storage of any allowances store, so service could be generic over it.
+pub trait AllowancesProvider: 'static {
+    type Store: AllowancesStore + 'static;
+
+    fn default() -> Result<(), AlreadySet<Self::Store>>;
+
+    fn get() -> &'static Self::Store;
+
+    fn get_mut() -> &'static mut Self::Store;
+}
+
+impl<S: Storage + 'static> AllowancesProvider for S
+where
+    S::Value: AllowancesStore,
+{
+    type Store = S::Value;
+
+    fn default() -> Result<(), AlreadySet<Self::Store>> {
+        <S as Storage>::default()
+    }
+
+    fn get() -> &'static Self::Store {
+        <S as Storage>::get()
+    }
+
+    fn get_mut() -> &'static mut Self::Store {
+        <S as Storage>::get_mut()
+    }
+}
+
+// Declares `Storage` backed by `static mut` on chain and by thread local natively,
+// so native tests running in parallel threads don't share state.
+macro_rules! storage {
+    ($vis:vis $name:ident: $value:ty = $default:expr) => {
+        $vis struct $name(());
+
+        impl $crate::services::erc20::storage::Storage for $name {
+            type Value = $value;
 
-        pub fn get() -> &'static BalancesMap {
-            if !Self::is_set() {
-                let _res = Self::default();
-                debug_assert!(_res.is_ok());
+            #[cfg(target_arch = "wasm32")]
+            unsafe fn slot() -> &'static mut Option<$value> {
+                static mut INSTANCE: Option<$value> = None;
+
+                // SAFETY: caller holds no other reference into the instance.
+                unsafe { &mut *core::ptr::addr_of_mut!(INSTANCE) }
             }
 
-            unsafe { INSTANCE.as_ref().expect("Infallible b/c set above") }
-        }
+            #[cfg(not(target_arch = "wasm32"))]
+            unsafe fn slot() -> &'static mut Option<$value> {
+                std::thread_local! {
+                    static INSTANCE: *mut Option<$value> = std::boxed::Box::into_raw(
+                        std::boxed::Box::new(None),
+                    );
+                }
 
-        pub fn get_mut() -> &'static mut BalancesMap {
-            if !Self::is_set() {
-                let _res = Self::default();
-                debug_assert!(_res.is_ok());
+                // SAFETY: box is never freed, and caller holds no other reference into it.
+                INSTANCE.with(|&instance| unsafe { &mut *instance })
             }
 
-            unsafe { INSTANCE.as_mut().expect("Infallible b/c set above") }
+            fn default_value() -> $value {
+                $default
+            }
         }
-    }
+    };
 }
 
-pub mod allowances {
+#[cfg(feature = "testing")]
+pub(crate) use storage;
+
+pub mod balances {
     use super::*;
+    use gstd::{collections::BTreeSet, ActorId};
 
-    pub struct AllowancesStorage(());
+    // Secondary index of holders in stable order.
+    pub type HoldersIndex = BTreeSet<ActorId>;
 
-    static mut INSTANCE: Option<AllowancesMap> = None;
+    storage!(pub BalancesStorage: BalancesMap = BalancesMap::with_capacity(u16::MAX as usize));
 
-    impl AllowancesStorage {
-        pub fn is_set() -> bool {
-            unsafe { INSTANCE.is_some() }
+    storage!(pub HoldersStorage: HoldersIndex = HoldersIndex::new());
+
+    impl HoldersStorage {
+        pub fn holders() -> impl Iterator<Item = ActorId> {
+            Self::get().iter().copied()
         }
 
-        pub fn set(value: AllowancesMap) -> Result<(), AllowancesMap> {
-            if Self::is_set() {
-                Err(value)
+        // Must follow any change of account's balance to keep index in sync.
+        pub fn reindex(balances: &impl BalancesStore, account: ActorId) {
+            let index = Self::get_mut();
+
+            if balances.contains_key(&account) {
+                index.insert(account);
             } else {
-                unsafe { INSTANCE = Some(value) }
-                Ok(())
+                index.remove(&account);
             }
         }
+    }
+}
+
+pub mod blocklist {
+    use super::*;
+
+    storage!(pub BlocklistStorage: BlocklistSet = BlocklistSet::new());
+}
+
+pub mod locks {
+    use super::*;
 
-        pub fn with_capacity(capacity: usize) -> Result<(), AllowancesMap> {
-            Self::set(AllowancesMap::with_capacity(capacity))
+    storage!(pub LocksStorage: LocksMap = LocksMap::new());
+}
+
+pub mod allowances {
+    use super::*;
+    use gstd::{
+        collections::{BTreeMap, BTreeSet},
+        ActorId,
+    };
+
+    // Secondary index of spenders by owner, both in stable order.
+    pub type SpendersIndex = BTreeMap<ActorId, BTreeSet<ActorId>>;
+
+    storage!(pub AllowancesStorage: AllowancesMap = AllowancesMap::with_capacity(u16::MAX as usize));
+
+    storage!(pub SpendersStorage: SpendersIndex = SpendersIndex::new());
+
+    impl SpendersStorage {
+        pub fn spenders_of(owner: ActorId) -> impl Iterator<Item = ActorId> {
+            Self::get().get(&owner).into_iter().flatten().copied()
         }
 
-        pub fn default() -> Result<(), AllowancesMap> {
-            Self::with_capacity(u16::MAX as usize)
+        pub fn approvals() -> impl Iterator<Item = (ActorId, ActorId)> {
+            Self::get().iter().flat_map(|(&owner, spenders)| {
+                spenders.iter().map(move |&spender| (owner, spender))
+            })
         }
 
-        pub fn get() -> &'static AllowancesMap {
-            if !Self::is_set() {
-                let _res = Self::default();
-                debug_assert!(_res.is_ok());
-            }
+        // Must follow any change of `(owner, spender)` allowance to keep index in sync.
+        pub fn reindex(allowances: &impl AllowancesStore, owner: ActorId, spender: ActorId) {
+            let index = Self::get_mut();
 
-            unsafe { INSTANCE.as_ref().expect("Infallible b/c set above") }
-        }
+            if allowances.contains_key(&(owner, spender)) {
+                index.entry(owner).or_default().insert(spender);
+            } else if let Some(spenders) = index.get_mut(&owner) {
+                spenders.remove(&spender);
 
-        pub fn get_mut() -> &'static mut AllowancesMap {
-            if !Self::is_set() {
-                let _res = Self::default();
-                debug_assert!(_res.is_ok());
+                if spenders.is_empty() {
+                    index.remove(&owner);
+                }
             }
-
-            unsafe { INSTANCE.as_mut().expect("Infallible b/c set above") }
         }
     }
 }
 
+pub mod nonces {
+    use super::*;
+
+    storage!(pub NoncesStorage: NoncesMap = NoncesMap::new());
+}
+
+pub mod import {
+    use super::*;
+
+    storage!(pub ImportStorage: PendingImport = None);
+}
+
+pub mod roles {
+    use crate::services::roles::RolesSet;
+
+    storage!(pub RolesStorage: RolesSet = RolesSet::new());
+}
+
 pub mod meta {
+    use super::*;
     use gstd::String;
-
-    pub struct MetaStorage(());
+    use primitive_types::U256;
 
     pub struct Meta {
         pub name: String,
         pub symbol: String,
         pub decimals: u8,
+        pub cap: Option<U256>,
     }
 
-    static mut INSTANCE: Option<Meta> = None;
+    storage!(pub MetaStorage: Meta = Meta {
+        name: String::from("Vara Network"),
+        symbol: String::from("VARA"),
+        decimals: 12,
+        cap: None,
+    });
 
     impl MetaStorage {
-        pub fn is_set() -> bool {
-            unsafe { INSTANCE.is_some() }
+        pub fn name() -> String {
+            Self::get().name.clone()
         }
 
-        pub fn set(name: String, symbol: String, decimals: u8) -> Result<(), Meta> {
-            let meta = Meta {
-                name,
-                symbol,
-                decimals,
-            };
+        pub fn symbol() -> String {
+            Self::get().symbol.clone()
+        }
 
-            if Self::is_set() {
-                Err(meta)
-            } else {
-                unsafe { INSTANCE = Some(meta) }
-                Ok(())
-            }
+        pub fn decimals() -> u8 {
+            Self::get().decimals
         }
 
-        pub fn default() -> Result<(), Meta> {
-            Self::set(String::from("Vara Network"), String::from("VARA"), 12)
+        pub fn cap() -> Option<U256> {
+            Self::get().cap
         }
+    }
+}
 
-        pub fn get() -> &'static Meta {
-            if !Self::is_set() {
-                let _res = Self::default();
-                debug_assert!(_res.is_ok());
-            }
+pub mod pause {
+    use super::*;
+    use gstd::collections::BTreeSet;
 
-            unsafe { INSTANCE.as_ref().expect("Infallible b/c set above") }
-        }
+    #[derive(Default)]
+    pub struct Pause {
+        pub global: bool,
+        pub operations: BTreeSet<Operation>,
+    }
 
-        pub fn name() -> String {
-            Self::get().name.clone()
-        }
+    storage!(pub PauseStorage: Pause = Pause::default());
 
-        pub fn symbol() -> String {
-            Self::get().symbol.clone()
-        }
+    impl PauseStorage {
+        pub fn is_paused(operation: Operation) -> bool {
+            let pause = Self::get();
 
-        pub fn decimals() -> u8 {
-            Self::get().decimals
+            pause.global || pause.operations.contains(&operation)
         }
     }
 }
 
+pub mod snapshots {
+    use super::*;
+
+    storage!(pub SnapshotsStorage: Snapshots = Snapshots::default());
+}
+
 pub mod total_supply {
     use primitive_types::U256;
 
-    pub struct TotalSupplyStorage(());
+    storage!(pub TotalSupplyStorage: U256 = U256::zero());
+}
 
-    static mut INSTANCE: Option<U256> = None;
+pub mod votes {
+    use super::*;
 
-    impl TotalSupplyStorage {
-        pub fn is_set() -> bool {
-            unsafe { INSTANCE.is_some() }
-        }
+    storage!(pub VotesStorage: Votes = Votes::default());
+}
 
-        pub fn set(value: U256) -> Result<(), U256> {
-            if Self::is_set() {
-                Err(value)
-            } else {
-                unsafe { INSTANCE = Some(value) }
-                Ok(())
-            }
+pub mod vesting {
+    use super::*;
+
+    storage!(pub VestingStorage: VestingMap = VestingMap::new());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    storage!(CounterStorage: u64 = 42);
+
+    #[test]
+    fn storage() {
+        // # Test case #1.
+        // Storage can't be accessed before set.
+        {
+            assert!(!CounterStorage::is_set());
+            assert_eq!(CounterStorage::try_get(), Err(NotSet));
+            assert_eq!(CounterStorage::try_get_mut(), Err(NotSet));
         }
 
-        pub fn default() -> Result<(), U256> {
-            Self::set(U256::zero())
+        // # Test case #2.
+        // Storage is set with default value.
+        {
+            assert_eq!(CounterStorage::default(), Ok(()));
+            assert!(CounterStorage::is_set());
+            assert_eq!(*CounterStorage::get(), 42);
         }
 
-        pub fn get() -> U256 {
-            if !Self::is_set() {
-                let _res = Self::default();
-                debug_assert!(_res.is_ok());
-            }
+        // # Test case #3.
+        // Storage is set only once.
+        {
+            *CounterStorage::get_mut() += 1;
 
-            unsafe { *INSTANCE.as_ref().expect("Infallible b/c set above") }
+            assert_eq!(CounterStorage::set(0), Err(AlreadySet(0)));
+            assert_eq!(CounterStorage::default(), Err(AlreadySet(42)));
+            assert_eq!(*CounterStorage::get(), 43);
         }
 
-        pub fn get_mut() -> &'static mut U256 {
-            if !Self::is_set() {
-                let _res = Self::default();
-                debug_assert!(_res.is_ok());
-            }
+        // # Test case #4.
+        // Storage isn't shared between threads natively.
+        {
+            let value = std::thread::spawn(|| CounterStorage::try_get().copied()).join();
 
-            unsafe { INSTANCE.as_mut().expect("Infallible b/c set above") }
+            assert_eq!(value.ok(), Some(Err(NotSet)));
         }
     }
 }
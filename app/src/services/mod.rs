@@ -1 +1,18 @@
+use core::fmt::Debug;
+use gstd::{ext, format};
+
+mod context;
+
 pub mod erc20;
+pub mod roles;
+
+fn panicking<T, E: Debug, F: FnOnce() -> Result<T, E>>(f: F) -> T {
+    match f() {
+        Ok(v) => v,
+        Err(e) => panic(e),
+    }
+}
+
+fn panic(err: impl Debug) -> ! {
+    ext::panic(&format!("{err:?}"))
+}
@@ -0,0 +1,295 @@
+pub type RolesSet = HashSet<(ActorId, Role)>;
+pub type Result<T, E = Error> = core::result::Result<T, E>;
+
+use gstd::{collections::HashSet, prelude::*, ActorId};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Encode, Decode, TypeInfo)]
+pub enum Role {
+    Admin,
+    Minter,
+    Burner,
+    Pauser,
+}
+
+impl Role {
+    pub const ALL: [Role; 4] = [Role::Admin, Role::Minter, Role::Burner, Role::Pauser];
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Encode, Decode, TypeInfo)]
+pub enum Event {
+    RoleGranted {
+        role: Role,
+        account: ActorId,
+        sender: ActorId,
+    },
+    RoleRevoked {
+        role: Role,
+        account: ActorId,
+        sender: ActorId,
+    },
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Encode, Decode, TypeInfo)]
+pub enum Error {
+    LastAdmin,
+    Unauthorized,
+}
+
+// The only failure of role check, so it converts into any service's error.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Unauthorized;
+
+impl From<Unauthorized> for Error {
+    fn from(_: Unauthorized) -> Self {
+        Self::Unauthorized
+    }
+}
+
+pub fn has_role(roles: &RolesSet, account: ActorId, role: Role) -> bool {
+    roles.contains(&(account, role))
+}
+
+pub fn ensure_role(roles: &RolesSet, account: ActorId, role: Role) -> Result<(), Unauthorized> {
+    has_role(roles, account, role)
+        .then_some(())
+        .ok_or(Unauthorized)
+}
+
+pub fn grant_role(
+    roles: &mut RolesSet,
+    sender: ActorId,
+    account: ActorId,
+    role: Role,
+) -> Result<bool> {
+    ensure_role(roles, sender, Role::Admin)?;
+
+    Ok(roles.insert((account, role)))
+}
+
+pub fn revoke_role(
+    roles: &mut RolesSet,
+    sender: ActorId,
+    account: ActorId,
+    role: Role,
+) -> Result<bool> {
+    ensure_role(roles, sender, Role::Admin)?;
+
+    remove_role(roles, account, role)
+}
+
+pub fn renounce_role(roles: &mut RolesSet, account: ActorId, role: Role) -> Result<bool> {
+    remove_role(roles, account, role)
+}
+
+// Last admin is kept, so roles could always be managed further.
+fn remove_role(roles: &mut RolesSet, account: ActorId, role: Role) -> Result<bool> {
+    let is_last_admin = role == Role::Admin
+        && roles
+            .iter()
+            .all(|&(holder, role)| role != Role::Admin || holder == account);
+
+    if is_last_admin && has_role(roles, account, role) {
+        return Err(Error::LastAdmin);
+    }
+
+    Ok(roles.remove(&(account, role)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use utils::*;
+
+    macro_rules! assert_ok {
+        ( $x:expr, $y: expr $(,)? ) => {{
+            assert_eq!($x.unwrap(), $y);
+        }};
+    }
+
+    macro_rules! assert_err {
+        ( $x:expr, $y: expr $(,)? ) => {{
+            assert_eq!($x.err().expect("Ran into Ok value"), $y);
+        }};
+    }
+
+    #[test]
+    fn has_role() {
+        // Initializing thread logger.
+        let _ = env_logger::try_init();
+
+        // Creating set with Alice as admin.
+        let set = roles_set([(alice(), Role::Admin)]);
+
+        // # Test case #1.
+        // Role is found if granted.
+        {
+            assert!(super::has_role(&set, alice(), Role::Admin));
+            assert_ok!(super::ensure_role(&set, alice(), Role::Admin), ());
+        }
+
+        // # Test case #2.
+        // Role isn't found if not granted.
+        {
+            assert!(!super::has_role(&set, alice(), Role::Minter));
+            assert!(!super::has_role(&set, bob(), Role::Admin));
+            assert_err!(super::ensure_role(&set, bob(), Role::Admin), Unauthorized);
+        }
+    }
+
+    #[test]
+    fn grant_role() {
+        // Initializing thread logger.
+        let _ = env_logger::try_init();
+
+        // Creating set with Alice as admin.
+        let mut set = roles_set([(alice(), Role::Admin)]);
+
+        // # Test case #1.
+        // Alice grants minter role to Bob.
+        {
+            assert_ok!(
+                super::grant_role(&mut set, alice(), bob(), Role::Minter),
+                true
+            );
+            assert!(super::has_role(&set, bob(), Role::Minter));
+        }
+
+        // # Test case #2.
+        // Granting already granted role is noop.
+        {
+            assert_ok!(
+                super::grant_role(&mut set, alice(), bob(), Role::Minter),
+                false
+            );
+            assert!(super::has_role(&set, bob(), Role::Minter));
+        }
+
+        // # Test case #3.
+        // Bob isn't admin, so he can't grant roles.
+        {
+            assert_err!(
+                super::grant_role(&mut set, bob(), charlie(), Role::Minter),
+                Error::Unauthorized
+            );
+            assert!(!super::has_role(&set, charlie(), Role::Minter));
+        }
+    }
+
+    #[test]
+    fn revoke_role() {
+        // Initializing thread logger.
+        let _ = env_logger::try_init();
+
+        // Creating set with Alice as admin and Bob as minter.
+        let mut set = roles_set([(alice(), Role::Admin), (bob(), Role::Minter)]);
+
+        // # Test case #1.
+        // Bob isn't admin, so he can't revoke roles.
+        {
+            assert_err!(
+                super::revoke_role(&mut set, bob(), bob(), Role::Minter),
+                Error::Unauthorized
+            );
+            assert!(super::has_role(&set, bob(), Role::Minter));
+        }
+
+        // # Test case #2.
+        // Alice revokes minter role from Bob.
+        {
+            assert_ok!(
+                super::revoke_role(&mut set, alice(), bob(), Role::Minter),
+                true
+            );
+            assert!(!super::has_role(&set, bob(), Role::Minter));
+        }
+
+        // # Test case #3.
+        // Revoking missing role is noop.
+        {
+            assert_ok!(
+                super::revoke_role(&mut set, alice(), bob(), Role::Minter),
+                false
+            );
+        }
+
+        // # Test case #4.
+        // Last admin can't be revoked, while any other one can.
+        {
+            assert_err!(
+                super::revoke_role(&mut set, alice(), alice(), Role::Admin),
+                Error::LastAdmin
+            );
+            assert!(super::has_role(&set, alice(), Role::Admin));
+
+            assert_ok!(
+                super::grant_role(&mut set, alice(), bob(), Role::Admin),
+                true
+            );
+            assert_ok!(
+                super::revoke_role(&mut set, bob(), alice(), Role::Admin),
+                true
+            );
+            assert!(!super::has_role(&set, alice(), Role::Admin));
+        }
+    }
+
+    #[test]
+    fn renounce_role() {
+        // Initializing thread logger.
+        let _ = env_logger::try_init();
+
+        // Creating set with Alice as admin and Bob as minter.
+        let mut set = roles_set([(alice(), Role::Admin), (bob(), Role::Minter)]);
+
+        // # Test case #1.
+        // Bob renounces his role.
+        {
+            assert_ok!(super::renounce_role(&mut set, bob(), Role::Minter), true);
+            assert!(!super::has_role(&set, bob(), Role::Minter));
+        }
+
+        // # Test case #2.
+        // Renouncing missing role is noop.
+        {
+            assert_ok!(super::renounce_role(&mut set, bob(), Role::Minter), false);
+        }
+
+        // # Test case #3.
+        // Last admin can't renounce, unless there's another one.
+        {
+            assert_err!(
+                super::renounce_role(&mut set, alice(), Role::Admin),
+                Error::LastAdmin
+            );
+            assert!(super::has_role(&set, alice(), Role::Admin));
+
+            assert_ok!(
+                super::grant_role(&mut set, alice(), bob(), Role::Admin),
+                true
+            );
+            assert_ok!(super::renounce_role(&mut set, alice(), Role::Admin), true);
+            assert!(!super::has_role(&set, alice(), Role::Admin));
+        }
+    }
+
+    mod utils {
+        use super::{Role, RolesSet};
+        use gstd::ActorId;
+
+        pub fn roles_set<const N: usize>(content: [(ActorId, Role); N]) -> RolesSet {
+            content.into_iter().collect()
+        }
+
+        pub fn alice() -> ActorId {
+            1u64.into()
+        }
+
+        pub fn bob() -> ActorId {
+            2u64.into()
+        }
+
+        pub fn charlie() -> ActorId {
+            3u64.into()
+        }
+    }
+}
@@ -0,0 +1,93 @@
+#![allow(clippy::unused_unit)]
+
+mod internal;
+
+pub use internal::*;
+
+use super::{
+    context,
+    erc20::storage::{roles::RolesStorage, Storage},
+    panic, panicking,
+};
+use gstd::{ActorId, Decode, Encode, TypeInfo, Vec};
+use sails_macros::gservice;
+use sails_rtl::gstd::events::EventTrigger;
+
+pub struct Service<X> {
+    informer: X,
+}
+
+impl<X> Service<X> {
+    pub fn seed(owner: ActorId) {
+        let roles = Role::ALL.into_iter().map(|role| (owner, role)).collect();
+
+        panicking(|| RolesStorage::set(roles));
+    }
+}
+
+impl<X: EventTrigger<Event>> Service<X> {
+    pub fn deposit_event(&self, e: Event) {
+        if self.informer.trigger(e).is_err() {
+            panic("Failed to deposit event");
+        }
+    }
+}
+
+#[gservice]
+impl<X: EventTrigger<Event>> Service<X> {
+    pub fn new(informer: X) -> Self {
+        Self { informer }
+    }
+
+    pub fn grant_role(&mut self, account: ActorId, role: Role) -> Result<bool, Error> {
+        let sender = context::source();
+
+        let mutated = grant_role(RolesStorage::get_mut(), sender, account, role)?;
+
+        if mutated {
+            self.deposit_event(Event::RoleGranted {
+                role,
+                account,
+                sender,
+            })
+        }
+
+        Ok(mutated)
+    }
+
+    pub fn has_role(&self, account: ActorId, role: Role) -> bool {
+        has_role(RolesStorage::get(), account, role)
+    }
+
+    pub fn renounce_role(&mut self, role: Role) -> Result<bool, Error> {
+        let account = context::source();
+
+        let mutated = renounce_role(RolesStorage::get_mut(), account, role)?;
+
+        if mutated {
+            self.deposit_event(Event::RoleRevoked {
+                role,
+                account,
+                sender: account,
+            })
+        }
+
+        Ok(mutated)
+    }
+
+    pub fn revoke_role(&mut self, account: ActorId, role: Role) -> Result<bool, Error> {
+        let sender = context::source();
+
+        let mutated = revoke_role(RolesStorage::get_mut(), sender, account, role)?;
+
+        if mutated {
+            self.deposit_event(Event::RoleRevoked {
+                role,
+                account,
+                sender,
+            })
+        }
+
+        Ok(mutated)
+    }
+}
@@ -0,0 +1,258 @@
+// Helpers for native service-level tests: recording event trigger and fake context.
+
+use crate::services::{
+    erc20::{
+        self,
+        storage::{
+            allowances::AllowancesStorage, balances::BalancesStorage, storage, AllowancesProvider,
+            BalancesProvider,
+        },
+        NonZeroU256,
+    },
+    roles,
+};
+use core::{
+    cell::RefCell,
+    future::Future,
+    pin::pin,
+    task::{Context as TaskContext, Poll, RawWaker, RawWakerVTable, Waker},
+};
+use gstd::{collections::BTreeMap, ActorId, String};
+use sails_rtl::{errors::Result, gstd::events::EventTrigger};
+use std::{rc::Rc, vec::Vec};
+
+// Records deposited events, sharing them between clones to be inspected after.
+pub struct RecordingTrigger<E>(Rc<RefCell<Vec<E>>>);
+
+impl<E> RecordingTrigger<E> {
+    pub fn new() -> Self {
+        Self(Default::default())
+    }
+
+    // Drains events recorded so far.
+    pub fn take(&self) -> Vec<E> {
+        self.0.take()
+    }
+}
+
+impl<E> Clone for RecordingTrigger<E> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<E> EventTrigger<E> for RecordingTrigger<E> {
+    fn trigger(&self, event: E) -> Result<()> {
+        self.0.borrow_mut().push(event);
+        Ok(())
+    }
+}
+
+// Context is thread local, as well as storages natively, so tests don't interfere.
+pub mod context {
+    use core::cell::{Cell, RefCell};
+    use gstd::{ActorId, Vec};
+    use std::boxed::Box;
+
+    // Replies to messages sent for reply, `None` standing for failed send or error reply.
+    type Replier = Box<dyn FnMut(ActorId, &[u8], u128) -> Option<Vec<u8>>>;
+
+    #[derive(Clone, Copy, Debug, Default)]
+    struct Context {
+        source: ActorId,
+        value: u128,
+        block_height: u32,
+        block_timestamp: u64,
+        program_id: ActorId,
+    }
+
+    std::thread_local! {
+        static CONTEXT: RefCell<Context> = RefCell::new(Context::default());
+        static REPLIER: RefCell<Option<Replier>> = const { RefCell::new(None) };
+        static SENT: RefCell<Vec<(ActorId, Vec<u8>, u128)>> = const { RefCell::new(Vec::new()) };
+        static EVICTING: Cell<bool> = const { Cell::new(false) };
+    }
+
+    fn get() -> Context {
+        CONTEXT.with(|context| *context.borrow())
+    }
+
+    fn update(f: impl FnOnce(&mut Context)) {
+        CONTEXT.with(|context| f(&mut context.borrow_mut()))
+    }
+
+    pub fn source() -> ActorId {
+        get().source
+    }
+
+    pub fn value() -> u128 {
+        get().value
+    }
+
+    pub fn block_height() -> u32 {
+        get().block_height
+    }
+
+    pub fn block_timestamp() -> u64 {
+        get().block_timestamp
+    }
+
+    pub fn program_id() -> ActorId {
+        get().program_id
+    }
+
+    // Sends never fail, being recorded to be inspected with `take_sent`.
+    pub fn send_value(to: ActorId, value: u128) -> bool {
+        SENT.with(|sent| sent.borrow_mut().push((to, Vec::new(), value)));
+        true
+    }
+
+    // Resolves at once, so commands could be run with `testing::block_on`.
+    pub async fn send_for_reply(to: ActorId, payload: Vec<u8>, value: u128) -> Option<Vec<u8>> {
+        let reply = REPLIER.with(|replier| {
+            replier
+                .borrow_mut()
+                .as_mut()
+                .and_then(|replier| replier(to, &payload, value))
+        });
+
+        SENT.with(|sent| sent.borrow_mut().push((to, payload, value)));
+
+        reply
+    }
+
+    // Reply arrives at once, so hook runs right away. Messages are never resumed
+    // after it while evicting, see `testing::poll_once`.
+    pub async fn send_with_reply_hook(
+        to: ActorId,
+        payload: Vec<u8>,
+        value: u128,
+        on_reply: impl FnOnce(bool) + 'static,
+    ) -> bool {
+        let reply = send_for_reply(to, payload, value).await;
+
+        on_reply(reply.is_some());
+
+        if EVICTING.get() {
+            core::future::pending::<()>().await
+        }
+
+        true
+    }
+
+    // Drains messages sent so far.
+    pub fn take_sent() -> Vec<(ActorId, Vec<u8>, u128)> {
+        SENT.with(|sent| sent.take())
+    }
+
+    // Without replier every message sent for reply fails.
+    pub fn set_replier(replier: impl FnMut(ActorId, &[u8], u128) -> Option<Vec<u8>> + 'static) {
+        REPLIER.with(|cell| *cell.borrow_mut() = Some(Box::new(replier)))
+    }
+
+    // Messages waiting for reply are evicted, while their reply hooks still run.
+    pub fn set_evicting(evicting: bool) {
+        EVICTING.set(evicting)
+    }
+
+    pub fn set_source(source: ActorId) {
+        update(|context| context.source = source)
+    }
+
+    pub fn set_value(value: u128) {
+        update(|context| context.value = value)
+    }
+
+    pub fn set_block_height(block_height: u32) {
+        update(|context| context.block_height = block_height)
+    }
+
+    pub fn set_block_timestamp(block_timestamp: u64) {
+        update(|context| context.block_timestamp = block_timestamp)
+    }
+
+    pub fn set_program_id(program_id: ActorId) {
+        update(|context| context.program_id = program_id)
+    }
+}
+
+// Fake context never leaves messages pending, unless evicting them.
+pub fn block_on<F: Future>(future: F) -> F::Output {
+    poll_once(future).expect("Future is pending, while fake context resolves at once")
+}
+
+// Pending future is dropped, as evicted message would be.
+pub fn poll_once<F: Future>(future: F) -> Option<F::Output> {
+    fn noop_raw_waker() -> RawWaker {
+        fn clone(_: *const ()) -> RawWaker {
+            noop_raw_waker()
+        }
+        fn noop(_: *const ()) {}
+
+        static VTABLE: RawWakerVTable = RawWakerVTable::new(clone, noop, noop, noop);
+
+        RawWaker::new(core::ptr::null(), &VTABLE)
+    }
+
+    // SAFETY: waker does nothing, so its contract is trivially upheld.
+    let waker = unsafe { Waker::from_raw(noop_raw_waker()) };
+    let mut future = pin!(future);
+
+    match future.as_mut().poll(&mut TaskContext::from_waker(&waker)) {
+        Poll::Ready(output) => Some(output),
+        Poll::Pending => None,
+    }
+}
+
+// Stores backed by `BTreeMap`, to test services with stores other than default ones.
+storage!(pub BTreeBalancesStorage: BTreeMap<ActorId, NonZeroU256> = BTreeMap::new());
+
+storage!(pub BTreeAllowancesStorage: BTreeMap<(ActorId, ActorId), (NonZeroU256, Option<u32>)> = BTreeMap::new());
+
+// Seeds token as if deployed by `admin`, so granted with all the roles.
+pub fn seed(admin: ActorId) {
+    seed_with::<BalancesStorage, AllowancesStorage>(admin)
+}
+
+pub fn seed_with<B: BalancesProvider, A: AllowancesProvider>(admin: ActorId) {
+    <erc20::Service<(), B, A>>::seed(String::from("Vara Network"), String::from("VARA"), 12, None);
+    <roles::Service<()>>::seed(admin);
+
+    // Program's account holds escrowed tokens, so it mustn't be zero one.
+    context::set_program_id(1.into());
+    context::set_source(admin);
+}
+
+// Seeds token from the first chunk of exported state, as if deployed by `admin`.
+pub fn seed_from_state(admin: ActorId, chunk: erc20::StateChunk) {
+    <erc20::Service<()>>::seed_from_state(chunk);
+    <roles::Service<()>>::seed(admin);
+
+    context::set_program_id(1.into());
+    context::set_source(admin);
+}
+
+pub fn erc20_service() -> (
+    erc20::Service<RecordingTrigger<erc20::Event>>,
+    RecordingTrigger<erc20::Event>,
+) {
+    erc20_service_with()
+}
+
+pub fn erc20_service_with<B: BalancesProvider, A: AllowancesProvider>() -> (
+    erc20::Service<RecordingTrigger<erc20::Event>, B, A>,
+    RecordingTrigger<erc20::Event>,
+) {
+    let trigger = RecordingTrigger::new();
+
+    (erc20::Service::new(trigger.clone()), trigger)
+}
+
+pub fn roles_service() -> (
+    roles::Service<RecordingTrigger<roles::Event>>,
+    RecordingTrigger<roles::Event>,
+) {
+    let trigger = RecordingTrigger::new();
+
+    (roles::Service::new(trigger.clone()), trigger)
+}
@@ -0,0 +1,853 @@
+use erc20::{
+    services::{
+        erc20::{
+            Error, Event, NonZeroU256, Operation, StateChunk, StateMeta, ON_TOKEN_RECEIVED,
+            RECEIVER_SERVICE, STATE_VERSION,
+        },
+        roles::{self, Role},
+    },
+    testing::{self, context, BTreeAllowancesStorage, BTreeBalancesStorage},
+};
+use gstd::{ActorId, Decode, Encode, String, Vec};
+use primitive_types::U256;
+
+// Deployer of the token, so granted with all the roles.
+const ALICE: u64 = 42;
+const BOB: u64 = 43;
+const CHARLIE: u64 = 44;
+
+#[test]
+fn mint_and_burn() {
+    testing::seed(actor(ALICE));
+
+    let (mut service, events) = testing::erc20_service();
+
+    // # Test case #1.
+    // Bob isn't minter, so he can't mint.
+    {
+        context::set_source(actor(BOB));
+
+        assert_eq!(
+            service.mint(actor(BOB), U256::exp10(20)),
+            Err(Error::Unauthorized)
+        );
+        assert!(service.total_supply().is_zero());
+        assert!(events.take().is_empty());
+    }
+
+    // # Test case #2.
+    // Alice mints to Bob.
+    {
+        context::set_source(actor(ALICE));
+
+        assert_eq!(service.mint(actor(BOB), U256::exp10(20)), Ok(true));
+        assert_eq!(
+            events.take(),
+            [Event::Transfer {
+                from: ActorId::zero(),
+                to: actor(BOB),
+                value: non_zero(U256::exp10(20)),
+            }]
+        );
+
+        assert_eq!(service.balance_of(actor(BOB)), U256::exp10(20));
+        assert_eq!(service.total_supply(), U256::exp10(20));
+    }
+
+    // # Test case #3.
+    // Bob burns part of his tokens.
+    {
+        context::set_source(actor(BOB));
+
+        assert_eq!(service.burn(actor(BOB), U256::exp10(19)), Ok(true));
+        assert_eq!(
+            events.take(),
+            [Event::Transfer {
+                from: actor(BOB),
+                to: ActorId::zero(),
+                value: non_zero(U256::exp10(19)),
+            }]
+        );
+
+        assert_eq!(
+            service.balance_of(actor(BOB)),
+            U256::exp10(20) - U256::exp10(19)
+        );
+        assert_eq!(service.total_supply(), U256::exp10(20) - U256::exp10(19));
+    }
+
+    // # Test case #4.
+    // Zero burn is noop.
+    {
+        assert_eq!(service.burn(actor(BOB), U256::zero()), Ok(false));
+        assert!(events.take().is_empty());
+    }
+
+    // # Test case #5.
+    // Charlie isn't burner, so he can't burn Bob's tokens, while Alice can.
+    {
+        context::set_source(actor(CHARLIE));
+
+        assert_eq!(
+            service.burn(actor(BOB), U256::one()),
+            Err(Error::Unauthorized)
+        );
+
+        context::set_source(actor(ALICE));
+
+        assert_eq!(service.burn(actor(BOB), U256::one()), Ok(true));
+        assert_eq!(
+            events.take(),
+            [Event::Transfer {
+                from: actor(BOB),
+                to: ActorId::zero(),
+                value: non_zero(U256::one()),
+            }]
+        );
+    }
+}
+
+#[test]
+fn approve_and_from_transfer() {
+    testing::seed(actor(ALICE));
+
+    let (mut service, events) = testing::erc20_service();
+
+    assert_eq!(service.mint(actor(ALICE), U256::exp10(20)), Ok(true));
+    events.take();
+
+    // # Test case #1.
+    // Bob can't transfer from Alice without allowance.
+    {
+        context::set_source(actor(BOB));
+
+        assert_eq!(
+            service.from_transfer(actor(ALICE), actor(CHARLIE), U256::exp10(19)),
+            Err(Error::InsufficientAllowance)
+        );
+        assert!(events.take().is_empty());
+    }
+
+    // # Test case #2.
+    // Alice approves Bob.
+    {
+        context::set_source(actor(ALICE));
+
+        assert_eq!(service.approve(actor(BOB), U256::exp10(19)), Ok(true));
+        assert_eq!(
+            events.take(),
+            [Event::Approval {
+                owner: actor(ALICE),
+                spender: actor(BOB),
+                value: U256::exp10(19),
+            }]
+        );
+
+        assert_eq!(service.allowance(actor(ALICE), actor(BOB)), U256::exp10(19));
+        assert_eq!(service.spenders_of(actor(ALICE)), [actor(BOB)]);
+    }
+
+    // # Test case #3.
+    // Bob spends whole allowance transferring to Charlie.
+    {
+        context::set_source(actor(BOB));
+
+        assert_eq!(
+            service.from_transfer(actor(ALICE), actor(CHARLIE), U256::exp10(19)),
+            Ok(true)
+        );
+        assert_eq!(
+            events.take(),
+            [Event::Transfer {
+                from: actor(ALICE),
+                to: actor(CHARLIE),
+                value: non_zero(U256::exp10(19)),
+            }]
+        );
+
+        assert!(service.allowance(actor(ALICE), actor(BOB)).is_zero());
+        assert!(service.spenders_of(actor(ALICE)).is_empty());
+        assert_eq!(service.balance_of(actor(CHARLIE)), U256::exp10(19));
+    }
+}
+
+#[test]
+fn pause_and_freeze() {
+    testing::seed(actor(ALICE));
+
+    let (mut service, events) = testing::erc20_service();
+
+    assert_eq!(service.mint(actor(BOB), U256::exp10(20)), Ok(true));
+    events.take();
+
+    // # Test case #1.
+    // Bob isn't pauser, so he can't pause.
+    {
+        context::set_source(actor(BOB));
+
+        assert_eq!(service.pause(), Err(Error::Unauthorized));
+        assert!(!service.paused());
+        assert!(events.take().is_empty());
+    }
+
+    // # Test case #2.
+    // Alice pauses, so transfers are rejected until unpaused.
+    {
+        context::set_source(actor(ALICE));
+
+        assert_eq!(service.pause(), Ok(true));
+        assert_eq!(service.pause(), Ok(false));
+        assert_eq!(events.take(), [Event::Paused { operation: None }]);
+
+        context::set_source(actor(BOB));
+
+        assert_eq!(
+            service.transfer(actor(CHARLIE), U256::exp10(19)),
+            Err(Error::Paused)
+        );
+        assert!(service.is_paused(Operation::Transfer));
+
+        context::set_source(actor(ALICE));
+
+        assert_eq!(service.unpause(), Ok(true));
+        assert_eq!(events.take(), [Event::Unpaused { operation: None }]);
+    }
+
+    // # Test case #3.
+    // Alice freezes Bob, so he can't transfer until unfrozen.
+    {
+        assert_eq!(service.freeze(actor(BOB)), Ok(true));
+        assert_eq!(
+            events.take(),
+            [Event::Frozen {
+                account: actor(BOB)
+            }]
+        );
+
+        context::set_source(actor(BOB));
+
+        assert_eq!(
+            service.transfer(actor(CHARLIE), U256::exp10(19)),
+            Err(Error::AccountFrozen)
+        );
+
+        context::set_source(actor(ALICE));
+
+        assert_eq!(service.unfreeze(actor(BOB)), Ok(true));
+        assert_eq!(
+            events.take(),
+            [Event::Unfrozen {
+                account: actor(BOB)
+            }]
+        );
+
+        context::set_source(actor(BOB));
+
+        assert_eq!(service.transfer(actor(CHARLIE), U256::exp10(19)), Ok(true));
+        assert_eq!(service.balance_of(actor(CHARLIE)), U256::exp10(19));
+    }
+}
+
+#[test]
+fn lock_and_snapshot() {
+    testing::seed(actor(ALICE));
+
+    let (mut service, events) = testing::erc20_service();
+
+    assert_eq!(service.mint(actor(BOB), U256::exp10(20)), Ok(true));
+    events.take();
+
+    // # Test case #1.
+    // Bob locks his tokens until block 10, so he can't transfer them before.
+    {
+        context::set_source(actor(BOB));
+        context::set_block_height(5);
+
+        assert_eq!(service.lock(U256::exp10(20), 10), Ok(true));
+        assert_eq!(
+            events.take(),
+            [Event::Locked {
+                account: actor(BOB),
+                value: non_zero(U256::exp10(20)),
+                until: 10,
+            }]
+        );
+
+        assert_eq!(
+            service.transfer(actor(CHARLIE), U256::exp10(19)),
+            Err(Error::BalanceLocked)
+        );
+        assert_eq!(service.locked_balance_of(actor(BOB)), U256::exp10(20));
+    }
+
+    // # Test case #2.
+    // Snapshot keeps balances at the moment it was taken.
+    {
+        context::set_source(actor(ALICE));
+
+        assert_eq!(service.snapshot(), Ok(1));
+        assert_eq!(events.take(), [Event::Snapshot { id: 1 }]);
+
+        context::set_source(actor(BOB));
+        context::set_block_height(10);
+
+        assert!(service.locked_balance_of(actor(BOB)).is_zero());
+        assert_eq!(service.transfer(actor(CHARLIE), U256::exp10(19)), Ok(true));
+
+        assert_eq!(service.balance_of_at(actor(BOB), 1), Ok(U256::exp10(20)));
+        assert_eq!(service.balance_of_at(actor(CHARLIE), 1), Ok(U256::zero()));
+        assert_eq!(service.total_supply_at(1), Ok(U256::exp10(20)));
+    }
+}
+
+#[test]
+fn deposit_and_withdraw() {
+    testing::seed(actor(ALICE));
+
+    let (mut service, events) = testing::erc20_service();
+
+    // # Test case #1.
+    // Bob deposits value, being minted with equal amount of tokens.
+    {
+        context::set_source(actor(BOB));
+        context::set_value(1_000);
+
+        assert_eq!(service.deposit(), Ok(true));
+        assert_eq!(
+            events.take(),
+            [
+                Event::Transfer {
+                    from: ActorId::zero(),
+                    to: actor(BOB),
+                    value: non_zero(1_000.into()),
+                },
+                Event::Deposit {
+                    account: actor(BOB),
+                    value: non_zero(1_000.into()),
+                }
+            ]
+        );
+
+        assert_eq!(service.balance_of(actor(BOB)), 1_000.into());
+        assert!(context::take_sent().is_empty());
+    }
+
+    // # Test case #2.
+    // Deposit of frozen account is rejected, so its value is sent back.
+    {
+        context::set_source(actor(ALICE));
+        context::set_value(0);
+
+        assert_eq!(service.freeze(actor(CHARLIE)), Ok(true));
+        events.take();
+
+        context::set_source(actor(CHARLIE));
+        context::set_value(500);
+
+        assert_eq!(service.deposit(), Err(Error::AccountFrozen));
+        assert!(events.take().is_empty());
+        assert_eq!(context::take_sent(), [(actor(CHARLIE), vec![], 500)]);
+        assert!(service.balance_of(actor(CHARLIE)).is_zero());
+    }
+
+    // # Test case #3.
+    // Bob withdraws part of his tokens, being sent equal value.
+    {
+        context::set_source(actor(BOB));
+        context::set_value(0);
+        context::set_replier(|_, _, _| Some(vec![]));
+
+        assert_eq!(testing::block_on(service.withdraw(400.into())), Ok(true));
+        assert_eq!(
+            events.take(),
+            [
+                Event::Transfer {
+                    from: actor(BOB),
+                    to: context::program_id(),
+                    value: non_zero(400.into()),
+                },
+                Event::Transfer {
+                    from: context::program_id(),
+                    to: ActorId::zero(),
+                    value: non_zero(400.into()),
+                },
+                Event::Withdrawal {
+                    account: actor(BOB),
+                    value: non_zero(400.into()),
+                }
+            ]
+        );
+
+        assert_eq!(context::take_sent(), [(actor(BOB), vec![], 400)]);
+        assert_eq!(service.balance_of(actor(BOB)), 600.into());
+        assert!(service.balance_of(context::program_id()).is_zero());
+        assert_eq!(service.total_supply(), 600.into());
+    }
+
+    // # Test case #4.
+    // Value isn't delivered to Bob, so escrowed tokens are returned to him.
+    {
+        context::set_replier(|_, _, _| None);
+
+        assert_eq!(
+            testing::block_on(service.withdraw(400.into())),
+            Err(Error::ValueSendFailed)
+        );
+        assert_eq!(
+            events.take(),
+            [
+                Event::Transfer {
+                    from: actor(BOB),
+                    to: context::program_id(),
+                    value: non_zero(400.into()),
+                },
+                Event::Transfer {
+                    from: context::program_id(),
+                    to: actor(BOB),
+                    value: non_zero(400.into()),
+                }
+            ]
+        );
+
+        assert_eq!(context::take_sent(), [(actor(BOB), vec![], 400)]);
+        assert_eq!(service.balance_of(actor(BOB)), 600.into());
+        assert!(service.balance_of(context::program_id()).is_zero());
+        assert_eq!(service.total_supply(), 600.into());
+    }
+
+    // # Test case #5.
+    // Bob can't withdraw more than he has.
+    {
+        assert_eq!(
+            testing::block_on(service.withdraw(1_000.into())),
+            Err(Error::InsufficientBalance)
+        );
+        assert!(events.take().is_empty());
+        assert!(context::take_sent().is_empty());
+    }
+
+    // # Test case #6.
+    // Message is evicted before reply, while reply hook still settles escrow.
+    {
+        context::set_evicting(true);
+
+        let escrow = [Event::Transfer {
+            from: actor(BOB),
+            to: context::program_id(),
+            value: non_zero(100.into()),
+        }];
+
+        // Value isn't delivered, so tokens are returned to Bob.
+        assert!(testing::poll_once(service.withdraw(100.into())).is_none());
+        assert_eq!(events.take(), escrow);
+        assert_eq!(context::take_sent(), [(actor(BOB), vec![], 100)]);
+        assert_eq!(service.balance_of(actor(BOB)), 600.into());
+        assert!(service.balance_of(context::program_id()).is_zero());
+        assert_eq!(service.total_supply(), 600.into());
+
+        // Value is delivered, so tokens are burned.
+        context::set_replier(|_, _, _| Some(vec![]));
+
+        assert!(testing::poll_once(service.withdraw(100.into())).is_none());
+        assert_eq!(events.take(), escrow);
+        assert_eq!(context::take_sent(), [(actor(BOB), vec![], 100)]);
+        assert_eq!(service.balance_of(actor(BOB)), 500.into());
+        assert!(service.balance_of(context::program_id()).is_zero());
+        assert_eq!(service.total_supply(), 500.into());
+
+        context::set_evicting(false);
+    }
+}
+
+#[test]
+fn transfer_and_approve_and_call() {
+    testing::seed(actor(ALICE));
+
+    let (mut service, events) = testing::erc20_service();
+
+    assert_eq!(service.mint(actor(BOB), U256::exp10(20)), Ok(true));
+    events.take();
+
+    context::set_replier(|to, payload, _| {
+        let (service, method, _, _, _) =
+            <(String, String, ActorId, U256, Vec<u8>)>::decode(&mut &payload[..]).ok()?;
+
+        // Charlie accepts tokens and allowances, while others reject them.
+        Some((service, method, to == actor(CHARLIE)).encode())
+    });
+
+    // # Test case #1.
+    // Bob transfers to Charlie, who accepts.
+    {
+        context::set_source(actor(BOB));
+
+        assert_eq!(
+            testing::block_on(service.transfer_and_call(actor(CHARLIE), U256::exp10(19), vec![42])),
+            Ok(true)
+        );
+        assert_eq!(
+            events.take(),
+            [
+                Event::Transfer {
+                    from: actor(BOB),
+                    to: context::program_id(),
+                    value: non_zero(U256::exp10(19)),
+                },
+                Event::Transfer {
+                    from: context::program_id(),
+                    to: actor(CHARLIE),
+                    value: non_zero(U256::exp10(19)),
+                }
+            ]
+        );
+
+        let payload = (
+            RECEIVER_SERVICE,
+            ON_TOKEN_RECEIVED,
+            actor(BOB),
+            U256::exp10(19),
+            vec![42u8],
+        )
+            .encode();
+        assert_eq!(context::take_sent(), [(actor(CHARLIE), payload, 0)]);
+        assert_eq!(service.balance_of(actor(CHARLIE)), U256::exp10(19));
+        assert!(service.balance_of(context::program_id()).is_zero());
+    }
+
+    // # Test case #2.
+    // Alice rejects Bob's transfer, so escrowed tokens are returned to Bob.
+    {
+        assert_eq!(
+            testing::block_on(service.transfer_and_call(actor(ALICE), U256::exp10(19), vec![])),
+            Err(Error::ReceiverRejected)
+        );
+        assert_eq!(
+            events.take(),
+            [
+                Event::Transfer {
+                    from: actor(BOB),
+                    to: context::program_id(),
+                    value: non_zero(U256::exp10(19)),
+                },
+                Event::Transfer {
+                    from: context::program_id(),
+                    to: actor(BOB),
+                    value: non_zero(U256::exp10(19)),
+                }
+            ]
+        );
+        context::take_sent();
+
+        assert!(service.balance_of(actor(ALICE)).is_zero());
+        assert!(service.balance_of(context::program_id()).is_zero());
+        assert_eq!(
+            service.balance_of(actor(BOB)),
+            U256::exp10(20) - U256::exp10(19)
+        );
+    }
+
+    // # Test case #3.
+    // Bob approves Charlie, who accepts.
+    {
+        assert_eq!(
+            testing::block_on(service.approve_and_call(actor(CHARLIE), U256::exp10(18), vec![])),
+            Ok(true)
+        );
+        assert_eq!(
+            events.take(),
+            [Event::Approval {
+                owner: actor(BOB),
+                spender: actor(CHARLIE),
+                value: U256::exp10(18),
+            }]
+        );
+        assert_eq!(context::take_sent().len(), 1);
+        assert_eq!(
+            service.allowance(actor(BOB), actor(CHARLIE)),
+            U256::exp10(18)
+        );
+    }
+
+    // # Test case #4.
+    // Alice rejects Bob's approval, so allowance is reverted.
+    {
+        assert_eq!(
+            testing::block_on(service.approve_and_call(actor(ALICE), U256::exp10(18), vec![])),
+            Err(Error::ReceiverRejected)
+        );
+        events.take();
+        context::take_sent();
+
+        assert!(service.allowance(actor(BOB), actor(ALICE)).is_zero());
+    }
+
+    // # Test case #5.
+    // Bob approves Alice anew while she handles the call, so newer approval is kept.
+    {
+        context::set_replier(|to, payload, _| {
+            let (service, method, _, _, _) =
+                <(String, String, ActorId, U256, Vec<u8>)>::decode(&mut &payload[..]).ok()?;
+
+            let (mut erc20, _) = testing::erc20_service();
+            assert_eq!(erc20.approve(to, U256::exp10(17)), Ok(true));
+
+            Some((service, method, false).encode())
+        });
+
+        assert_eq!(
+            testing::block_on(service.approve_and_call(actor(ALICE), U256::exp10(18), vec![])),
+            Err(Error::ReceiverRejected)
+        );
+        assert_eq!(
+            events.take(),
+            [Event::Approval {
+                owner: actor(BOB),
+                spender: actor(ALICE),
+                value: U256::exp10(18),
+            }]
+        );
+        context::take_sent();
+
+        assert_eq!(service.allowance(actor(BOB), actor(ALICE)), U256::exp10(17));
+    }
+}
+
+#[test]
+fn import_and_pause() {
+    let meta = StateMeta {
+        name: "Vara Network".into(),
+        symbol: "VARA".into(),
+        decimals: 12,
+        cap: None,
+        total_supply: U256::exp10(21),
+    };
+
+    let chunk = |offset, balances, next| StateChunk {
+        version: STATE_VERSION,
+        offset,
+        meta: meta.clone(),
+        balances,
+        allowances: vec![],
+        next,
+    };
+
+    testing::seed_from_state(
+        actor(ALICE),
+        chunk(0, vec![(actor(BOB), U256::exp10(20))], Some(1)),
+    );
+
+    let (mut service, events) = testing::erc20_service();
+
+    // # Test case #1.
+    // Token stays paused and delegation waits until import completes.
+    {
+        assert!(service.paused());
+        assert!(service.is_paused(Operation::Approve));
+
+        context::set_source(actor(BOB));
+
+        assert_eq!(service.delegate(actor(BOB)), Err(Error::Paused));
+        assert!(events.take().is_empty());
+    }
+
+    // # Test case #2.
+    // Supply is fixed until import completes, so burning mid-import fails.
+    {
+        assert_eq!(service.burn(actor(BOB), U256::one()), Err(Error::Paused));
+        assert_eq!(
+            service.burn_from(actor(BOB), U256::one()),
+            Err(Error::Paused)
+        );
+        assert_eq!(
+            testing::block_on(service.withdraw(U256::one())),
+            Err(Error::Paused)
+        );
+
+        context::set_source(actor(ALICE));
+
+        assert_eq!(service.burn(actor(BOB), U256::one()), Err(Error::Paused));
+
+        assert!(events.take().is_empty());
+        assert!(context::take_sent().is_empty());
+        assert_eq!(service.total_supply(), U256::exp10(20));
+    }
+
+    // # Test case #3.
+    // Pauser's pause survives import completion.
+    {
+        assert_eq!(service.pause(), Ok(true));
+        events.take();
+
+        let last = chunk(
+            1,
+            vec![(actor(CHARLIE), U256::exp10(21) - U256::exp10(20))],
+            None,
+        );
+
+        assert_eq!(service.import_state(last), Ok(true));
+        assert!(events.take().is_empty());
+        assert!(service.paused());
+
+        assert_eq!(service.unpause(), Ok(true));
+        assert_eq!(events.take(), [Event::Unpaused { operation: None }]);
+        assert!(!service.paused());
+    }
+
+    // # Test case #4.
+    // Imported balances are delegated once import completes.
+    {
+        context::set_source(actor(CHARLIE));
+
+        assert_eq!(service.delegate(actor(CHARLIE)), Ok(true));
+        assert_eq!(
+            service.get_votes(actor(CHARLIE)),
+            U256::exp10(21) - U256::exp10(20)
+        );
+        assert_eq!(service.holders_count(), 2);
+    }
+}
+
+#[test]
+fn btree_stores() {
+    testing::seed_with::<BTreeBalancesStorage, BTreeAllowancesStorage>(actor(ALICE));
+
+    let (mut service, events) =
+        testing::erc20_service_with::<BTreeBalancesStorage, BTreeAllowancesStorage>();
+
+    // # Test case #1.
+    // Alice mints to Bob.
+    {
+        assert_eq!(service.mint(actor(BOB), U256::exp10(20)), Ok(true));
+        assert_eq!(
+            events.take(),
+            [Event::Transfer {
+                from: ActorId::zero(),
+                to: actor(BOB),
+                value: non_zero(U256::exp10(20)),
+            }]
+        );
+
+        assert_eq!(service.balance_of(actor(BOB)), U256::exp10(20));
+        assert_eq!(service.holders_count(), 1);
+    }
+
+    // # Test case #2.
+    // Bob transfers to Alice.
+    {
+        context::set_source(actor(BOB));
+
+        assert_eq!(service.transfer(actor(ALICE), U256::exp10(19)), Ok(true));
+        assert_eq!(
+            events.take(),
+            [Event::Transfer {
+                from: actor(BOB),
+                to: actor(ALICE),
+                value: non_zero(U256::exp10(19)),
+            }]
+        );
+
+        assert_eq!(service.balance_of(actor(ALICE)), U256::exp10(19));
+        assert_eq!(
+            service.balance_of(actor(BOB)),
+            U256::exp10(20) - U256::exp10(19)
+        );
+        assert_eq!(service.holders_count(), 2);
+    }
+
+    // # Test case #3.
+    // Bob approves Charlie.
+    {
+        assert_eq!(service.approve(actor(CHARLIE), U256::exp10(19)), Ok(true));
+        assert_eq!(
+            events.take(),
+            [Event::Approval {
+                owner: actor(BOB),
+                spender: actor(CHARLIE),
+                value: U256::exp10(19),
+            }]
+        );
+
+        assert_eq!(
+            service.allowance(actor(BOB), actor(CHARLIE)),
+            U256::exp10(19)
+        );
+    }
+
+    // # Test case #4.
+    // Charlie spends whole allowance transferring to himself.
+    {
+        context::set_source(actor(CHARLIE));
+
+        assert_eq!(
+            service.from_transfer(actor(BOB), actor(CHARLIE), U256::exp10(19)),
+            Ok(true)
+        );
+        assert_eq!(
+            events.take(),
+            [Event::Transfer {
+                from: actor(BOB),
+                to: actor(CHARLIE),
+                value: non_zero(U256::exp10(19)),
+            }]
+        );
+
+        assert!(service.allowance(actor(BOB), actor(CHARLIE)).is_zero());
+        assert_eq!(service.balance_of(actor(CHARLIE)), U256::exp10(19));
+        assert_eq!(service.holders_count(), 3);
+    }
+}
+
+#[test]
+fn roles() {
+    testing::seed(actor(ALICE));
+
+    let (mut service, events) = testing::roles_service();
+
+    // # Test case #1.
+    // Alice grants minter role to Bob.
+    {
+        assert_eq!(service.grant_role(actor(BOB), Role::Minter), Ok(true));
+        assert_eq!(
+            events.take(),
+            [roles::Event::RoleGranted {
+                role: Role::Minter,
+                account: actor(BOB),
+                sender: actor(ALICE),
+            }]
+        );
+
+        assert!(service.has_role(actor(BOB), Role::Minter));
+    }
+
+    // # Test case #2.
+    // Bob mints being granted with minter role.
+    {
+        let (mut erc20, _) = testing::erc20_service();
+
+        context::set_source(actor(BOB));
+
+        assert_eq!(erc20.mint(actor(CHARLIE), U256::exp10(20)), Ok(true));
+        assert_eq!(erc20.balance_of(actor(CHARLIE)), U256::exp10(20));
+    }
+
+    // # Test case #3.
+    // Alice can't renounce admin role being the last admin.
+    {
+        context::set_source(actor(ALICE));
+
+        assert_eq!(
+            service.renounce_role(Role::Admin),
+            Err(roles::Error::LastAdmin)
+        );
+        assert!(service.has_role(actor(ALICE), Role::Admin));
+        assert!(events.take().is_empty());
+    }
+}
+
+fn actor(id: u64) -> ActorId {
+    id.into()
+}
+
+fn non_zero(value: U256) -> NonZeroU256 {
+    value.try_into().unwrap()
+}
@@ -0,0 +1,13 @@
+use std::{env, path::PathBuf};
+
+fn main() {
+    let manifest_dir_path = PathBuf::from(env::var("CARGO_MANIFEST_DIR").unwrap());
+
+    let idl_file_path = manifest_dir_path.join("../wasm/erc20.idl");
+
+    let client_file_path = PathBuf::from(env::var("OUT_DIR").unwrap()).join("erc20_client.rs");
+
+    println!("cargo:rerun-if-changed={}", idl_file_path.display());
+
+    sails_clientgen::generate_client_from_idl(idl_file_path, client_file_path).unwrap();
+}
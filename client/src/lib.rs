@@ -0,0 +1,18 @@
+#![cfg_attr(not(feature = "gtest"), no_std)]
+
+// Typed bindings of `wasm/erc20.idl`, calling program through given remoting:
+//
+// let mut erc20 = Erc20::new(GStdRemoting);
+// let reply = erc20.transfer(to, value).publish(program_id).await?.reply().await?;
+
+include!(concat!(env!("OUT_DIR"), "/erc20_client.rs"));
+
+#[cfg(feature = "gstd")]
+pub mod gstd {
+    pub use sails_rtl::gstd::calls::{GStdArgs, GStdRemoting};
+}
+
+#[cfg(feature = "gtest")]
+pub mod gtest {
+    pub use sails_rtl::gtest::calls::{GTestArgs, GTestRemoting};
+}
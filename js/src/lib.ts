@@ -6,26 +6,107 @@ export type ActorId = `0x${string}`
 
 export type U256 = bigint
 
+export type Error = "AccountFrozen" | "AccountNotFrozen" | "AllowanceExpired" | "AllowanceUnderflow" | "BalanceLocked" | "BatchTooLarge" | "CapExceeded" | "FutureLookup" | "ImportClosed" | "InsufficientAllowance" | "InsufficientBalance" | "InvalidLock" | "InvalidNonce" | "InvalidSchedule" | "InvalidSignature" | "NotRevocable" | "NumericOverflow" | "Paused" | "PermitExpired" | "ReceiverRejected" | "SnapshotNotFound" | "StateMismatch" | "TooManyLocks" | "Unauthorized" | "UnsupportedVersion" | "ValueSendFailed" | "VestingExists" | "VestingNotFound";
+
+export type Operation = "Approve" | "Mint" | "Transfer";
+
+export type RolesError = "LastAdmin" | "Unauthorized";
+
+export type Role = "Admin" | "Minter" | "Burner" | "Pauser";
+
+export type Signature = 
+  | { ed25519: Array<number> }
+  | { sr25519: Array<number> };
+
+export interface StateChunk {
+  version: number;
+  offset: number;
+  meta: StateMeta;
+  balances: Array<[ActorId, U256]>;
+  allowances: Array<[ActorId, ActorId, U256, number | null]>;
+  next: number | null;
+}
+
+export interface StateMeta {
+  name: string;
+  symbol: string;
+  decimals: number;
+  cap: U256 | null;
+  total_supply: U256;
+}
+
+export interface VestingSchedule {
+  beneficiary: ActorId;
+  total: U256;
+  released: U256;
+  start: number | string | bigint;
+  cliff: number | string | bigint;
+  duration: number | string | bigint;
+  revocable: boolean;
+}
+
 export class ERC20 {
-  private registry: TypeRegistry;
+  public readonly registry: TypeRegistry;
+  public readonly roles: Roles;
+
   constructor(public api: GearApi, public programId?: `0x${string}`) {
     const types: Record<string, any> = {
       ActorId: "([u8; 32])",
       U256: "([u64; 4])",
+      Error: {"_enum":["AccountFrozen","AccountNotFrozen","AllowanceExpired","AllowanceUnderflow","BalanceLocked","BatchTooLarge","CapExceeded","FutureLookup","ImportClosed","InsufficientAllowance","InsufficientBalance","InvalidLock","InvalidNonce","InvalidSchedule","InvalidSignature","NotRevocable","NumericOverflow","Paused","PermitExpired","ReceiverRejected","SnapshotNotFound","StateMismatch","TooManyLocks","Unauthorized","UnsupportedVersion","ValueSendFailed","VestingExists","VestingNotFound"]},
+      Operation: {"_enum":["Approve","Mint","Transfer"]},
+      RolesError: {"_enum":["LastAdmin","Unauthorized"]},
+      Role: {"_enum":["Admin","Minter","Burner","Pauser"]},
+      Signature: {"_enum":{"Ed25519":"[u8; 64]","Sr25519":"[u8; 64]"}},
+      StateChunk: {"version":"u8", "offset":"u32", "meta":"StateMeta", "balances":"Vec<(ActorId, U256)>", "allowances":"Vec<(ActorId, ActorId, U256, Option<u32>)>", "next":"Option<u32>"},
+      StateMeta: {"name":"String", "symbol":"String", "decimals":"u8", "cap":"Option<U256>", "total_supply":"U256"},
+      VestingSchedule: {"beneficiary":"ActorId", "total":"U256", "released":"U256", "start":"u64", "cliff":"u64", "duration":"u64", "revocable":"bool"},
     }
 
     this.registry = new TypeRegistry();
     this.registry.setKnownTypes({ types });
     this.registry.register(types);
+
+    this.roles = new Roles(this);
+  }
+
+  newCtorFromCode(code: Uint8Array | Buffer, name: string, symbol: string, decimals: number, cap: U256 | null): TransactionBuilder<null> {
+    const builder = new TransactionBuilder<null>(
+      this.api,
+      this.registry,
+      'upload_program',
+      ['New', name, symbol, decimals, cap],
+      '(String, String, String, u8, Option<U256>)',
+      'String',
+      code,
+    );
+
+    this.programId = builder.programId;
+    return builder;
+  }
+
+  newCtorFromCodeId(codeId: `0x${string}`, name: string, symbol: string, decimals: number, cap: U256 | null) {
+    const builder = new TransactionBuilder<null>(
+      this.api,
+      this.registry,
+      'create_program',
+      ['New', name, symbol, decimals, cap],
+      '(String, String, String, u8, Option<U256>)',
+      'String',
+      codeId,
+    );
+
+    this.programId = builder.programId;
+    return builder;
   }
 
-  newCtorFromCode(code: Uint8Array | Buffer, name: string, symbol: string, decimals: number | string): TransactionBuilder<null> {
+  fromStateCtorFromCode(code: Uint8Array | Buffer, chunk: StateChunk): TransactionBuilder<null> {
     const builder = new TransactionBuilder<null>(
       this.api,
       this.registry,
       'upload_program',
-      ['New', name, symbol, decimals],
-      '(String, String, String, u8)',
+      ['FromState', chunk],
+      '(String, StateChunk)',
       'String',
       code,
     );
@@ -34,13 +115,13 @@ export class ERC20 {
     return builder;
   }
 
-  newCtorFromCodeId(codeId: `0x${string}`, name: string, symbol: string, decimals: number | string) {
+  fromStateCtorFromCodeId(codeId: `0x${string}`, chunk: StateChunk) {
     const builder = new TransactionBuilder<null>(
       this.api,
       this.registry,
       'create_program',
-      ['New', name, symbol, decimals],
-      '(String, String, String, u8)',
+      ['FromState', chunk],
+      '(String, StateChunk)',
       'String',
       codeId,
     );
@@ -49,50 +130,386 @@ export class ERC20 {
     return builder;
   }
 
-  public approve(spender: ActorId, value: U256): TransactionBuilder<boolean> {
-    return new TransactionBuilder<boolean>(
+  public approve(spender: ActorId, value: U256): TransactionBuilder<{ ok: boolean } | { err: Error }> {
+    return new TransactionBuilder<{ ok: boolean } | { err: Error }>(
       this.api,
       this.registry,
       'send_message',
       ['Approve', spender, value],
       '(String, ActorId, U256)',
-      'bool',
+      'Result<bool, Error>',
+      this.programId
+    );
+  }
+
+  public approveAndCall(spender: ActorId, value: U256, data: Array<number>): TransactionBuilder<{ ok: boolean } | { err: Error }> {
+    return new TransactionBuilder<{ ok: boolean } | { err: Error }>(
+      this.api,
+      this.registry,
+      'send_message',
+      ['ApproveAndCall', spender, value, data],
+      '(String, ActorId, U256, Vec<u8>)',
+      'Result<bool, Error>',
+      this.programId
+    );
+  }
+
+  public approveWithExpiry(spender: ActorId, value: U256, expires_at: number): TransactionBuilder<{ ok: boolean } | { err: Error }> {
+    return new TransactionBuilder<{ ok: boolean } | { err: Error }>(
+      this.api,
+      this.registry,
+      'send_message',
+      ['ApproveWithExpiry', spender, value, expires_at],
+      '(String, ActorId, U256, u32)',
+      'Result<bool, Error>',
       this.programId
     );
   }
 
-  public fromTransfer(from: ActorId, to: ActorId, value: U256): TransactionBuilder<boolean> {
-    return new TransactionBuilder<boolean>(
+  public batchTransfer(transfers: Array<[ActorId, U256]>): TransactionBuilder<{ ok: boolean } | { err: Error }> {
+    return new TransactionBuilder<{ ok: boolean } | { err: Error }>(
+      this.api,
+      this.registry,
+      'send_message',
+      ['BatchTransfer', transfers],
+      '(String, Vec<(ActorId, U256)>)',
+      'Result<bool, Error>',
+      this.programId
+    );
+  }
+
+  public batchTransferFrom(from: ActorId, transfers: Array<[ActorId, U256]>): TransactionBuilder<{ ok: boolean } | { err: Error }> {
+    return new TransactionBuilder<{ ok: boolean } | { err: Error }>(
+      this.api,
+      this.registry,
+      'send_message',
+      ['BatchTransferFrom', from, transfers],
+      '(String, ActorId, Vec<(ActorId, U256)>)',
+      'Result<bool, Error>',
+      this.programId
+    );
+  }
+
+  public burn(from: ActorId, value: U256): TransactionBuilder<{ ok: boolean } | { err: Error }> {
+    return new TransactionBuilder<{ ok: boolean } | { err: Error }>(
+      this.api,
+      this.registry,
+      'send_message',
+      ['Burn', from, value],
+      '(String, ActorId, U256)',
+      'Result<bool, Error>',
+      this.programId
+    );
+  }
+
+  public burnFrom(from: ActorId, value: U256): TransactionBuilder<{ ok: boolean } | { err: Error }> {
+    return new TransactionBuilder<{ ok: boolean } | { err: Error }>(
+      this.api,
+      this.registry,
+      'send_message',
+      ['BurnFrom', from, value],
+      '(String, ActorId, U256)',
+      'Result<bool, Error>',
+      this.programId
+    );
+  }
+
+  public createVesting(beneficiary: ActorId, total: U256, start: number | string | bigint, cliff: number | string | bigint, duration: number | string | bigint, revocable: boolean): TransactionBuilder<{ ok: boolean } | { err: Error }> {
+    return new TransactionBuilder<{ ok: boolean } | { err: Error }>(
+      this.api,
+      this.registry,
+      'send_message',
+      ['CreateVesting', beneficiary, total, start, cliff, duration, revocable],
+      '(String, ActorId, U256, u64, u64, u64, bool)',
+      'Result<bool, Error>',
+      this.programId
+    );
+  }
+
+  public decreaseAllowance(spender: ActorId, delta: U256): TransactionBuilder<{ ok: boolean } | { err: Error }> {
+    return new TransactionBuilder<{ ok: boolean } | { err: Error }>(
+      this.api,
+      this.registry,
+      'send_message',
+      ['DecreaseAllowance', spender, delta],
+      '(String, ActorId, U256)',
+      'Result<bool, Error>',
+      this.programId
+    );
+  }
+
+  public delegate(delegatee: ActorId): TransactionBuilder<{ ok: boolean } | { err: Error }> {
+    return new TransactionBuilder<{ ok: boolean } | { err: Error }>(
+      this.api,
+      this.registry,
+      'send_message',
+      ['Delegate', delegatee],
+      '(String, ActorId)',
+      'Result<bool, Error>',
+      this.programId
+    );
+  }
+
+  public deposit(): TransactionBuilder<{ ok: boolean } | { err: Error }> {
+    return new TransactionBuilder<{ ok: boolean } | { err: Error }>(
+      this.api,
+      this.registry,
+      'send_message',
+      ['Deposit'],
+      '(String)',
+      'Result<bool, Error>',
+      this.programId
+    );
+  }
+
+  public freeze(account: ActorId): TransactionBuilder<{ ok: boolean } | { err: Error }> {
+    return new TransactionBuilder<{ ok: boolean } | { err: Error }>(
+      this.api,
+      this.registry,
+      'send_message',
+      ['Freeze', account],
+      '(String, ActorId)',
+      'Result<bool, Error>',
+      this.programId
+    );
+  }
+
+  public fromTransfer(from: ActorId, to: ActorId, value: U256): TransactionBuilder<{ ok: boolean } | { err: Error }> {
+    return new TransactionBuilder<{ ok: boolean } | { err: Error }>(
       this.api,
       this.registry,
       'send_message',
       ['FromTransfer', from, to, value],
       '(String, ActorId, ActorId, U256)',
-      'bool',
+      'Result<bool, Error>',
       this.programId
     );
   }
 
-  public setBalance(new_balance: U256): TransactionBuilder<boolean> {
-    return new TransactionBuilder<boolean>(
+  public importState(chunk: StateChunk): TransactionBuilder<{ ok: boolean } | { err: Error }> {
+    return new TransactionBuilder<{ ok: boolean } | { err: Error }>(
       this.api,
       this.registry,
       'send_message',
-      ['SetBalance', new_balance],
-      '(String, U256)',
-      'bool',
+      ['ImportState', chunk],
+      '(String, StateChunk)',
+      'Result<bool, Error>',
+      this.programId
+    );
+  }
+
+  public increaseAllowance(spender: ActorId, delta: U256): TransactionBuilder<{ ok: boolean } | { err: Error }> {
+    return new TransactionBuilder<{ ok: boolean } | { err: Error }>(
+      this.api,
+      this.registry,
+      'send_message',
+      ['IncreaseAllowance', spender, delta],
+      '(String, ActorId, U256)',
+      'Result<bool, Error>',
+      this.programId
+    );
+  }
+
+  public lock(value: U256, until: number): TransactionBuilder<{ ok: boolean } | { err: Error }> {
+    return new TransactionBuilder<{ ok: boolean } | { err: Error }>(
+      this.api,
+      this.registry,
+      'send_message',
+      ['Lock', value, until],
+      '(String, U256, u32)',
+      'Result<bool, Error>',
+      this.programId
+    );
+  }
+
+  public mint(to: ActorId, value: U256): TransactionBuilder<{ ok: boolean } | { err: Error }> {
+    return new TransactionBuilder<{ ok: boolean } | { err: Error }>(
+      this.api,
+      this.registry,
+      'send_message',
+      ['Mint', to, value],
+      '(String, ActorId, U256)',
+      'Result<bool, Error>',
+      this.programId
+    );
+  }
+
+  public pause(): TransactionBuilder<{ ok: boolean } | { err: Error }> {
+    return new TransactionBuilder<{ ok: boolean } | { err: Error }>(
+      this.api,
+      this.registry,
+      'send_message',
+      ['Pause'],
+      '(String)',
+      'Result<bool, Error>',
+      this.programId
+    );
+  }
+
+  public pauseOperation(operation: Operation): TransactionBuilder<{ ok: boolean } | { err: Error }> {
+    return new TransactionBuilder<{ ok: boolean } | { err: Error }>(
+      this.api,
+      this.registry,
+      'send_message',
+      ['PauseOperation', operation],
+      '(String, Operation)',
+      'Result<bool, Error>',
+      this.programId
+    );
+  }
+
+  public permit(owner: ActorId, spender: ActorId, value: U256, deadline: number | string | bigint, signature: Signature): TransactionBuilder<{ ok: boolean } | { err: Error }> {
+    return new TransactionBuilder<{ ok: boolean } | { err: Error }>(
+      this.api,
+      this.registry,
+      'send_message',
+      ['Permit', owner, spender, value, deadline, signature],
+      '(String, ActorId, ActorId, U256, u64, Signature)',
+      'Result<bool, Error>',
+      this.programId
+    );
+  }
+
+  public release(): TransactionBuilder<{ ok: boolean } | { err: Error }> {
+    return new TransactionBuilder<{ ok: boolean } | { err: Error }>(
+      this.api,
+      this.registry,
+      'send_message',
+      ['Release'],
+      '(String)',
+      'Result<bool, Error>',
       this.programId
     );
   }
 
-  public transfer(to: ActorId, value: U256): TransactionBuilder<boolean> {
-    return new TransactionBuilder<boolean>(
+  public revokeAll(): TransactionBuilder<{ ok: boolean } | { err: Error }> {
+    return new TransactionBuilder<{ ok: boolean } | { err: Error }>(
+      this.api,
+      this.registry,
+      'send_message',
+      ['RevokeAll'],
+      '(String)',
+      'Result<bool, Error>',
+      this.programId
+    );
+  }
+
+  public revokeVesting(beneficiary: ActorId): TransactionBuilder<{ ok: boolean } | { err: Error }> {
+    return new TransactionBuilder<{ ok: boolean } | { err: Error }>(
+      this.api,
+      this.registry,
+      'send_message',
+      ['RevokeVesting', beneficiary],
+      '(String, ActorId)',
+      'Result<bool, Error>',
+      this.programId
+    );
+  }
+
+  public seize(from: ActorId, to: ActorId): TransactionBuilder<{ ok: boolean } | { err: Error }> {
+    return new TransactionBuilder<{ ok: boolean } | { err: Error }>(
+      this.api,
+      this.registry,
+      'send_message',
+      ['Seize', from, to],
+      '(String, ActorId, ActorId)',
+      'Result<bool, Error>',
+      this.programId
+    );
+  }
+
+  public snapshot(): TransactionBuilder<{ ok: number | string | bigint } | { err: Error }> {
+    return new TransactionBuilder<{ ok: number | string | bigint } | { err: Error }>(
+      this.api,
+      this.registry,
+      'send_message',
+      ['Snapshot'],
+      '(String)',
+      'Result<u64, Error>',
+      this.programId
+    );
+  }
+
+  public transfer(to: ActorId, value: U256): TransactionBuilder<{ ok: boolean } | { err: Error }> {
+    return new TransactionBuilder<{ ok: boolean } | { err: Error }>(
       this.api,
       this.registry,
       'send_message',
       ['Transfer', to, value],
       '(String, ActorId, U256)',
-      'bool',
+      'Result<bool, Error>',
+      this.programId
+    );
+  }
+
+  public transferAndCall(to: ActorId, value: U256, data: Array<number>): TransactionBuilder<{ ok: boolean } | { err: Error }> {
+    return new TransactionBuilder<{ ok: boolean } | { err: Error }>(
+      this.api,
+      this.registry,
+      'send_message',
+      ['TransferAndCall', to, value, data],
+      '(String, ActorId, U256, Vec<u8>)',
+      'Result<bool, Error>',
+      this.programId
+    );
+  }
+
+  public transferLocked(to: ActorId, value: U256, unlock_at: number): TransactionBuilder<{ ok: boolean } | { err: Error }> {
+    return new TransactionBuilder<{ ok: boolean } | { err: Error }>(
+      this.api,
+      this.registry,
+      'send_message',
+      ['TransferLocked', to, value, unlock_at],
+      '(String, ActorId, U256, u32)',
+      'Result<bool, Error>',
+      this.programId
+    );
+  }
+
+  public unfreeze(account: ActorId): TransactionBuilder<{ ok: boolean } | { err: Error }> {
+    return new TransactionBuilder<{ ok: boolean } | { err: Error }>(
+      this.api,
+      this.registry,
+      'send_message',
+      ['Unfreeze', account],
+      '(String, ActorId)',
+      'Result<bool, Error>',
+      this.programId
+    );
+  }
+
+  public unpause(): TransactionBuilder<{ ok: boolean } | { err: Error }> {
+    return new TransactionBuilder<{ ok: boolean } | { err: Error }>(
+      this.api,
+      this.registry,
+      'send_message',
+      ['Unpause'],
+      '(String)',
+      'Result<bool, Error>',
+      this.programId
+    );
+  }
+
+  public unpauseOperation(operation: Operation): TransactionBuilder<{ ok: boolean } | { err: Error }> {
+    return new TransactionBuilder<{ ok: boolean } | { err: Error }>(
+      this.api,
+      this.registry,
+      'send_message',
+      ['UnpauseOperation', operation],
+      '(String, Operation)',
+      'Result<bool, Error>',
+      this.programId
+    );
+  }
+
+  public withdraw(value: U256): TransactionBuilder<{ ok: boolean } | { err: Error }> {
+    return new TransactionBuilder<{ ok: boolean } | { err: Error }>(
+      this.api,
+      this.registry,
+      'send_message',
+      ['Withdraw', value],
+      '(String, U256)',
+      'Result<bool, Error>',
       this.programId
     );
   }
@@ -111,6 +528,20 @@ export class ERC20 {
     return result[1].toBigInt() as unknown as U256;
   }
 
+  public async allowancesOf(owner: ActorId, offset: number, limit: number, originAddress: string, value?: number | string | bigint, atBlock?: `0x${string}`): Promise<Array<[ActorId, U256]>> {
+    const payload = this.registry.createType('(String, ActorId, u32, u32)', ['AllowancesOf', owner, offset, limit]).toU8a();
+    const reply = await this.api.message.calculateReply({
+      destination: this.programId,
+      origin: decodeAddress(originAddress),
+      payload,
+      value: value || 0,
+      gasLimit: this.api.blockGasLimit.toBigInt(),
+      at: atBlock || null,
+    });
+    const result = this.registry.createType('(String, Vec<(ActorId, U256)>)', reply.payload);
+    return result[1].toJSON() as unknown as Array<[ActorId, U256]>;
+  }
+
   public async balanceOf(owner: ActorId, originAddress: string, value?: number | string | bigint, atBlock?: `0x${string}`): Promise<U256> {
     const payload = this.registry.createType('(String, ActorId)', ['BalanceOf', owner]).toU8a();
     const reply = await this.api.message.calculateReply({
@@ -125,7 +556,35 @@ export class ERC20 {
     return result[1].toBigInt() as unknown as U256;
   }
 
-  public async decimals(originAddress: string, value?: number | string | bigint, atBlock?: `0x${string}`): Promise<number | string> {
+  public async balanceOfAt(owner: ActorId, id: number | string | bigint, originAddress: string, value?: number | string | bigint, atBlock?: `0x${string}`): Promise<{ ok: U256 } | { err: Error }> {
+    const payload = this.registry.createType('(String, ActorId, u64)', ['BalanceOfAt', owner, id]).toU8a();
+    const reply = await this.api.message.calculateReply({
+      destination: this.programId,
+      origin: decodeAddress(originAddress),
+      payload,
+      value: value || 0,
+      gasLimit: this.api.blockGasLimit.toBigInt(),
+      at: atBlock || null,
+    });
+    const result = this.registry.createType('(String, Result<U256, Error>)', reply.payload);
+    return result[1].toJSON() as unknown as { ok: U256 } | { err: Error };
+  }
+
+  public async cap(originAddress: string, value?: number | string | bigint, atBlock?: `0x${string}`): Promise<U256 | null> {
+    const payload = this.registry.createType('String', 'Cap').toU8a();
+    const reply = await this.api.message.calculateReply({
+      destination: this.programId,
+      origin: decodeAddress(originAddress),
+      payload,
+      value: value || 0,
+      gasLimit: this.api.blockGasLimit.toBigInt(),
+      at: atBlock || null,
+    });
+    const result = this.registry.createType('(String, Option<U256>)', reply.payload);
+    return result[1].toJSON() as unknown as U256 | null;
+  }
+
+  public async decimals(originAddress: string, value?: number | string | bigint, atBlock?: `0x${string}`): Promise<number> {
     const payload = this.registry.createType('String', 'Decimals').toU8a();
     const reply = await this.api.message.calculateReply({
       destination: this.programId,
@@ -136,7 +595,147 @@ export class ERC20 {
       at: atBlock || null,
     });
     const result = this.registry.createType('(String, u8)', reply.payload);
-    return result[1].toNumber() as unknown as number | string;
+    return result[1].toNumber() as unknown as number;
+  }
+
+  public async delegates(account: ActorId, originAddress: string, value?: number | string | bigint, atBlock?: `0x${string}`): Promise<ActorId> {
+    const payload = this.registry.createType('(String, ActorId)', ['Delegates', account]).toU8a();
+    const reply = await this.api.message.calculateReply({
+      destination: this.programId,
+      origin: decodeAddress(originAddress),
+      payload,
+      value: value || 0,
+      gasLimit: this.api.blockGasLimit.toBigInt(),
+      at: atBlock || null,
+    });
+    const result = this.registry.createType('(String, ActorId)', reply.payload);
+    return result[1].toJSON() as unknown as ActorId;
+  }
+
+  public async domainSeparator(originAddress: string, value?: number | string | bigint, atBlock?: `0x${string}`): Promise<Array<number>> {
+    const payload = this.registry.createType('String', 'DomainSeparator').toU8a();
+    const reply = await this.api.message.calculateReply({
+      destination: this.programId,
+      origin: decodeAddress(originAddress),
+      payload,
+      value: value || 0,
+      gasLimit: this.api.blockGasLimit.toBigInt(),
+      at: atBlock || null,
+    });
+    const result = this.registry.createType('(String, [u8; 32])', reply.payload);
+    return result[1].toJSON() as unknown as Array<number>;
+  }
+
+  public async exportState(offset: number, limit: number, originAddress: string, value?: number | string | bigint, atBlock?: `0x${string}`): Promise<{ ok: StateChunk } | { err: Error }> {
+    const payload = this.registry.createType('(String, u32, u32)', ['ExportState', offset, limit]).toU8a();
+    const reply = await this.api.message.calculateReply({
+      destination: this.programId,
+      origin: decodeAddress(originAddress),
+      payload,
+      value: value || 0,
+      gasLimit: this.api.blockGasLimit.toBigInt(),
+      at: atBlock || null,
+    });
+    const result = this.registry.createType('(String, Result<StateChunk, Error>)', reply.payload);
+    return result[1].toJSON() as unknown as { ok: StateChunk } | { err: Error };
+  }
+
+  public async getPastVotes(account: ActorId, block: number, originAddress: string, value?: number | string | bigint, atBlock?: `0x${string}`): Promise<{ ok: U256 } | { err: Error }> {
+    const payload = this.registry.createType('(String, ActorId, u32)', ['GetPastVotes', account, block]).toU8a();
+    const reply = await this.api.message.calculateReply({
+      destination: this.programId,
+      origin: decodeAddress(originAddress),
+      payload,
+      value: value || 0,
+      gasLimit: this.api.blockGasLimit.toBigInt(),
+      at: atBlock || null,
+    });
+    const result = this.registry.createType('(String, Result<U256, Error>)', reply.payload);
+    return result[1].toJSON() as unknown as { ok: U256 } | { err: Error };
+  }
+
+  public async getVotes(account: ActorId, originAddress: string, value?: number | string | bigint, atBlock?: `0x${string}`): Promise<U256> {
+    const payload = this.registry.createType('(String, ActorId)', ['GetVotes', account]).toU8a();
+    const reply = await this.api.message.calculateReply({
+      destination: this.programId,
+      origin: decodeAddress(originAddress),
+      payload,
+      value: value || 0,
+      gasLimit: this.api.blockGasLimit.toBigInt(),
+      at: atBlock || null,
+    });
+    const result = this.registry.createType('(String, U256)', reply.payload);
+    return result[1].toBigInt() as unknown as U256;
+  }
+
+  public async holders(offset: number, limit: number, originAddress: string, value?: number | string | bigint, atBlock?: `0x${string}`): Promise<Array<[ActorId, U256]>> {
+    const payload = this.registry.createType('(String, u32, u32)', ['Holders', offset, limit]).toU8a();
+    const reply = await this.api.message.calculateReply({
+      destination: this.programId,
+      origin: decodeAddress(originAddress),
+      payload,
+      value: value || 0,
+      gasLimit: this.api.blockGasLimit.toBigInt(),
+      at: atBlock || null,
+    });
+    const result = this.registry.createType('(String, Vec<(ActorId, U256)>)', reply.payload);
+    return result[1].toJSON() as unknown as Array<[ActorId, U256]>;
+  }
+
+  public async holdersCount(originAddress: string, value?: number | string | bigint, atBlock?: `0x${string}`): Promise<number> {
+    const payload = this.registry.createType('String', 'HoldersCount').toU8a();
+    const reply = await this.api.message.calculateReply({
+      destination: this.programId,
+      origin: decodeAddress(originAddress),
+      payload,
+      value: value || 0,
+      gasLimit: this.api.blockGasLimit.toBigInt(),
+      at: atBlock || null,
+    });
+    const result = this.registry.createType('(String, u32)', reply.payload);
+    return result[1].toNumber() as unknown as number;
+  }
+
+  public async isFrozen(account: ActorId, originAddress: string, value?: number | string | bigint, atBlock?: `0x${string}`): Promise<boolean> {
+    const payload = this.registry.createType('(String, ActorId)', ['IsFrozen', account]).toU8a();
+    const reply = await this.api.message.calculateReply({
+      destination: this.programId,
+      origin: decodeAddress(originAddress),
+      payload,
+      value: value || 0,
+      gasLimit: this.api.blockGasLimit.toBigInt(),
+      at: atBlock || null,
+    });
+    const result = this.registry.createType('(String, bool)', reply.payload);
+    return result[1].toJSON() as unknown as boolean;
+  }
+
+  public async isPaused(operation: Operation, originAddress: string, value?: number | string | bigint, atBlock?: `0x${string}`): Promise<boolean> {
+    const payload = this.registry.createType('(String, Operation)', ['IsPaused', operation]).toU8a();
+    const reply = await this.api.message.calculateReply({
+      destination: this.programId,
+      origin: decodeAddress(originAddress),
+      payload,
+      value: value || 0,
+      gasLimit: this.api.blockGasLimit.toBigInt(),
+      at: atBlock || null,
+    });
+    const result = this.registry.createType('(String, bool)', reply.payload);
+    return result[1].toJSON() as unknown as boolean;
+  }
+
+  public async lockedBalanceOf(account: ActorId, originAddress: string, value?: number | string | bigint, atBlock?: `0x${string}`): Promise<U256> {
+    const payload = this.registry.createType('(String, ActorId)', ['LockedBalanceOf', account]).toU8a();
+    const reply = await this.api.message.calculateReply({
+      destination: this.programId,
+      origin: decodeAddress(originAddress),
+      payload,
+      value: value || 0,
+      gasLimit: this.api.blockGasLimit.toBigInt(),
+      at: atBlock || null,
+    });
+    const result = this.registry.createType('(String, U256)', reply.payload);
+    return result[1].toBigInt() as unknown as U256;
   }
 
   public async name(originAddress: string, value?: number | string | bigint, atBlock?: `0x${string}`): Promise<string> {
@@ -153,6 +752,62 @@ export class ERC20 {
     return result[1].toString() as unknown as string;
   }
 
+  public async nonces(owner: ActorId, originAddress: string, value?: number | string | bigint, atBlock?: `0x${string}`): Promise<number | string | bigint> {
+    const payload = this.registry.createType('(String, ActorId)', ['Nonces', owner]).toU8a();
+    const reply = await this.api.message.calculateReply({
+      destination: this.programId,
+      origin: decodeAddress(originAddress),
+      payload,
+      value: value || 0,
+      gasLimit: this.api.blockGasLimit.toBigInt(),
+      at: atBlock || null,
+    });
+    const result = this.registry.createType('(String, u64)', reply.payload);
+    return result[1].toBigInt() as unknown as number | string | bigint;
+  }
+
+  public async paused(originAddress: string, value?: number | string | bigint, atBlock?: `0x${string}`): Promise<boolean> {
+    const payload = this.registry.createType('String', 'Paused').toU8a();
+    const reply = await this.api.message.calculateReply({
+      destination: this.programId,
+      origin: decodeAddress(originAddress),
+      payload,
+      value: value || 0,
+      gasLimit: this.api.blockGasLimit.toBigInt(),
+      at: atBlock || null,
+    });
+    const result = this.registry.createType('(String, bool)', reply.payload);
+    return result[1].toJSON() as unknown as boolean;
+  }
+
+  public async releasableAmount(beneficiary: ActorId, at: number | string | bigint, originAddress: string, value?: number | string | bigint, atBlock?: `0x${string}`): Promise<U256> {
+    const payload = this.registry.createType('(String, ActorId, u64)', ['ReleasableAmount', beneficiary, at]).toU8a();
+    const reply = await this.api.message.calculateReply({
+      destination: this.programId,
+      origin: decodeAddress(originAddress),
+      payload,
+      value: value || 0,
+      gasLimit: this.api.blockGasLimit.toBigInt(),
+      at: atBlock || null,
+    });
+    const result = this.registry.createType('(String, U256)', reply.payload);
+    return result[1].toBigInt() as unknown as U256;
+  }
+
+  public async spendersOf(owner: ActorId, originAddress: string, value?: number | string | bigint, atBlock?: `0x${string}`): Promise<Array<ActorId>> {
+    const payload = this.registry.createType('(String, ActorId)', ['SpendersOf', owner]).toU8a();
+    const reply = await this.api.message.calculateReply({
+      destination: this.programId,
+      origin: decodeAddress(originAddress),
+      payload,
+      value: value || 0,
+      gasLimit: this.api.blockGasLimit.toBigInt(),
+      at: atBlock || null,
+    });
+    const result = this.registry.createType('(String, Vec<ActorId>)', reply.payload);
+    return result[1].toJSON() as unknown as Array<ActorId>;
+  }
+
   public async symbol(originAddress: string, value?: number | string | bigint, atBlock?: `0x${string}`): Promise<string> {
     const payload = this.registry.createType('String', 'Symbol').toU8a();
     const reply = await this.api.message.calculateReply({
@@ -167,6 +822,20 @@ export class ERC20 {
     return result[1].toString() as unknown as string;
   }
 
+  public async topHolders(n: number, originAddress: string, value?: number | string | bigint, atBlock?: `0x${string}`): Promise<Array<[ActorId, U256]>> {
+    const payload = this.registry.createType('(String, u32)', ['TopHolders', n]).toU8a();
+    const reply = await this.api.message.calculateReply({
+      destination: this.programId,
+      origin: decodeAddress(originAddress),
+      payload,
+      value: value || 0,
+      gasLimit: this.api.blockGasLimit.toBigInt(),
+      at: atBlock || null,
+    });
+    const result = this.registry.createType('(String, Vec<(ActorId, U256)>)', reply.payload);
+    return result[1].toJSON() as unknown as Array<[ActorId, U256]>;
+  }
+
   public async totalSupply(originAddress: string, value?: number | string | bigint, atBlock?: `0x${string}`): Promise<U256> {
     const payload = this.registry.createType('String', 'TotalSupply').toU8a();
     const reply = await this.api.message.calculateReply({
@@ -180,4 +849,100 @@ export class ERC20 {
     const result = this.registry.createType('(String, U256)', reply.payload);
     return result[1].toBigInt() as unknown as U256;
   }
+
+  public async totalSupplyAt(id: number | string | bigint, originAddress: string, value?: number | string | bigint, atBlock?: `0x${string}`): Promise<{ ok: U256 } | { err: Error }> {
+    const payload = this.registry.createType('(String, u64)', ['TotalSupplyAt', id]).toU8a();
+    const reply = await this.api.message.calculateReply({
+      destination: this.programId,
+      origin: decodeAddress(originAddress),
+      payload,
+      value: value || 0,
+      gasLimit: this.api.blockGasLimit.toBigInt(),
+      at: atBlock || null,
+    });
+    const result = this.registry.createType('(String, Result<U256, Error>)', reply.payload);
+    return result[1].toJSON() as unknown as { ok: U256 } | { err: Error };
+  }
+
+  public async vestedAmount(beneficiary: ActorId, at: number | string | bigint, originAddress: string, value?: number | string | bigint, atBlock?: `0x${string}`): Promise<U256> {
+    const payload = this.registry.createType('(String, ActorId, u64)', ['VestedAmount', beneficiary, at]).toU8a();
+    const reply = await this.api.message.calculateReply({
+      destination: this.programId,
+      origin: decodeAddress(originAddress),
+      payload,
+      value: value || 0,
+      gasLimit: this.api.blockGasLimit.toBigInt(),
+      at: atBlock || null,
+    });
+    const result = this.registry.createType('(String, U256)', reply.payload);
+    return result[1].toBigInt() as unknown as U256;
+  }
+
+  public async vestingSchedule(beneficiary: ActorId, originAddress: string, value?: number | string | bigint, atBlock?: `0x${string}`): Promise<VestingSchedule | null> {
+    const payload = this.registry.createType('(String, ActorId)', ['VestingSchedule', beneficiary]).toU8a();
+    const reply = await this.api.message.calculateReply({
+      destination: this.programId,
+      origin: decodeAddress(originAddress),
+      payload,
+      value: value || 0,
+      gasLimit: this.api.blockGasLimit.toBigInt(),
+      at: atBlock || null,
+    });
+    const result = this.registry.createType('(String, Option<VestingSchedule>)', reply.payload);
+    return result[1].toJSON() as unknown as VestingSchedule | null;
+  }
+}
+
+export class Roles {
+  constructor(private _program: ERC20) {}
+
+  public grantRole(account: ActorId, role: Role): TransactionBuilder<{ ok: boolean } | { err: RolesError }> {
+    return new TransactionBuilder<{ ok: boolean } | { err: RolesError }>(
+      this._program.api,
+      this._program.registry,
+      'send_message',
+      ['Roles', 'GrantRole', account, role],
+      '(String, String, ActorId, Role)',
+      'Result<bool, RolesError>',
+      this._program.programId
+    );
+  }
+
+  public renounceRole(role: Role): TransactionBuilder<{ ok: boolean } | { err: RolesError }> {
+    return new TransactionBuilder<{ ok: boolean } | { err: RolesError }>(
+      this._program.api,
+      this._program.registry,
+      'send_message',
+      ['Roles', 'RenounceRole', role],
+      '(String, String, Role)',
+      'Result<bool, RolesError>',
+      this._program.programId
+    );
+  }
+
+  public revokeRole(account: ActorId, role: Role): TransactionBuilder<{ ok: boolean } | { err: RolesError }> {
+    return new TransactionBuilder<{ ok: boolean } | { err: RolesError }>(
+      this._program.api,
+      this._program.registry,
+      'send_message',
+      ['Roles', 'RevokeRole', account, role],
+      '(String, String, ActorId, Role)',
+      'Result<bool, RolesError>',
+      this._program.programId
+    );
+  }
+
+  public async hasRole(account: ActorId, role: Role, originAddress: string, value?: number | string | bigint, atBlock?: `0x${string}`): Promise<boolean> {
+    const payload = this._program.registry.createType('(String, String, ActorId, Role)', ['Roles', 'HasRole', account, role]).toU8a();
+    const reply = await this._program.api.message.calculateReply({
+      destination: this._program.programId,
+      origin: decodeAddress(originAddress),
+      payload,
+      value: value || 0,
+      gasLimit: this._program.api.blockGasLimit.toBigInt(),
+      at: atBlock || null,
+    });
+    const result = this._program.registry.createType('(String, String, bool)', reply.payload);
+    return result[2].toJSON() as unknown as boolean;
+  }
 }
\ No newline at end of file
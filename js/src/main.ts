@@ -6,11 +6,11 @@ import assert from 'assert/strict';
 import { ERC20 } from './lib.js';
 
 const upload = async (api: GearApi, account: IKeyringPair) => {
-  const code = readFileSync('../erc20_wasm.opt.wasm');
+  const code = readFileSync('../target/wasm32-unknown-unknown/release/erc20_wasm.opt.wasm');
 
   const grc = new ERC20(api);
 
-  const transaction = await grc.newCtorFromCode(code, 'VARA_TOKEN', 'TOK', 5).withAccount(account).calculateGas();
+  const transaction = await grc.newCtorFromCode(code, 'VARA_TOKEN', 'TOK', 5, null).withAccount(account).calculateGas();
 
   const { msgId, blockHash, response } = await transaction.signAndSend();
 
@@ -23,9 +23,9 @@ const upload = async (api: GearApi, account: IKeyringPair) => {
   return grc;
 };
 
-const mint = async (grc: ERC20, account: IKeyringPair) => {
+const mint = async (grc: ERC20, account: IKeyringPair, to: string) => {
   const transaction = await grc
-    .setBalance(BigInt(100 * 1e5))
+    .mint(decodeAddress(to), BigInt(100 * 1e5))
     .withAccount(account)
     .calculateGas();
 
@@ -34,6 +34,7 @@ const mint = async (grc: ERC20, account: IKeyringPair) => {
   console.log(`\nMint message added to block ${blockHash}. Message id: ${msgId}`);
 
   const result = await response();
+  assert.deepStrictEqual(result, { ok: true }, 'Mint should succeed');
 
   console.log('Mint message executed successfully.');
 };
@@ -49,6 +50,7 @@ const transfer = async (grc: ERC20, account: IKeyringPair, to: string) => {
   console.log(`\nTransfer message added to block ${blockHash}. Message id: ${msgId}`);
 
   const result = await response();
+  assert.deepStrictEqual(result, { ok: true }, 'Transfer should succeed');
 
   console.log('Transfer message executed successfully.');
 };
@@ -64,6 +66,7 @@ const approve = async (grc: ERC20, account: IKeyringPair, spender: string) => {
   console.log(`\nApprove message added to block ${blockHash}. Message id: ${msgId}`);
 
   const result = await response();
+  assert.deepStrictEqual(result, { ok: true }, 'Approve should succeed');
 
   console.log('Approve message executed successfully.');
 };
@@ -79,6 +82,7 @@ const transferFrom = async (grc: ERC20, account: IKeyringPair, from: string, to:
   console.log(`\nTransferFrom message added to block ${blockHash}. Message id: ${msgId}`);
 
   const result = await response();
+  assert.deepStrictEqual(result, { ok: true }, 'TransferFrom should succeed');
 
   console.log('TransferFrom message executed successfully.');
 };
@@ -104,7 +108,7 @@ const main = async () => {
   const grc = await upload(api, alice);
 
   // Mint 100 tokens to Alice
-  await mint(grc, alice);
+  await mint(grc, alice, alice.address);
 
   // Check Alice balance
   let aliceBalance = BigInt(await grc.balanceOf(aliceAddress, aliceAddress));
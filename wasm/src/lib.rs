@@ -2,3 +2,11 @@
 
 #[cfg(target_arch = "wasm32")]
 pub use erc20::wasm::*;
+
+#[cfg(not(target_arch = "wasm32"))]
+pub use code::WASM_BINARY_OPT as WASM_BINARY;
+
+#[cfg(not(target_arch = "wasm32"))]
+mod code {
+    include!(concat!(env!("OUT_DIR"), "/wasm_binary.rs"));
+}
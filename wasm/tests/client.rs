@@ -0,0 +1,83 @@
+use erc20_client::{
+    gtest::{GTestArgs, GTestRemoting},
+    traits::Service as _,
+    Error, Service,
+};
+use gtest::Program;
+use parity_scale_codec::Encode;
+use primitive_types::U256;
+use sails_rtl::{calls::Call, ActorId};
+
+// Deployer of the program, so granted with all the roles.
+const ALICE: u64 = 42;
+const BOB: u64 = 43;
+
+#[tokio::test]
+async fn mint_and_transfer() {
+    let remoting = GTestRemoting::new();
+    remoting.system().init_logger();
+
+    let program = Program::from_binary_with_id(remoting.system(), 1, erc20_wasm::WASM_BINARY);
+    let res = program.send_bytes(
+        ALICE,
+        ("New", "Vara Network", "VARA", 12u8, None::<U256>).encode(),
+    );
+    assert!(!res.main_failed());
+
+    let program_id = program.id().into_bytes().into();
+    let mut client = Service::new(remoting.clone());
+
+    // # Test case #1.
+    // Bob isn't minter, so he can't mint.
+    {
+        let res = client
+            .mint(actor(BOB), U256::exp10(20))
+            .with_args(GTestArgs::new(actor(BOB)))
+            .send_recv(program_id)
+            .await
+            .unwrap();
+        assert_eq!(res, Err(Error::Unauthorized));
+    }
+
+    // # Test case #2.
+    // Alice mints to Bob.
+    {
+        let res = client
+            .mint(actor(BOB), U256::exp10(20))
+            .with_args(GTestArgs::new(actor(ALICE)))
+            .send_recv(program_id)
+            .await
+            .unwrap();
+        assert_eq!(res, Ok(true));
+
+        let balance = client
+            .balance_of(actor(BOB))
+            .send_recv(program_id)
+            .await
+            .unwrap();
+        assert_eq!(balance, U256::exp10(20));
+    }
+
+    // # Test case #3.
+    // Bob transfers to Alice.
+    {
+        let res = client
+            .transfer(actor(ALICE), U256::exp10(19))
+            .with_args(GTestArgs::new(actor(BOB)))
+            .send_recv(program_id)
+            .await
+            .unwrap();
+        assert_eq!(res, Ok(true));
+
+        let balance = client
+            .balance_of(actor(ALICE))
+            .send_recv(program_id)
+            .await
+            .unwrap();
+        assert_eq!(balance, U256::exp10(19));
+    }
+}
+
+fn actor(id: u64) -> ActorId {
+    id.into()
+}
@@ -0,0 +1,1064 @@
+use ed25519_dalek::Signer;
+use erc20::services::erc20::{Error, Event, NonZeroU256, Operation, Permit, Signature, StateChunk};
+use gstd::ActorId;
+use gtest::{Log, Program, RunResult, System, WasmProgram};
+use parity_scale_codec::{Decode, DecodeAll, Encode};
+use primitive_types::U256;
+
+// Deployer of the program, so granted with all the roles.
+const ALICE: u64 = 42;
+const BOB: u64 = 43;
+const CHARLIE: u64 = 44;
+
+#[test]
+fn new() {
+    let system = System::new();
+    system.init_logger();
+
+    let program = init(&system, Some(U256::exp10(42)));
+
+    // # Test case #1.
+    // Meta is set from constructor.
+    {
+        assert_eq!(query::<String>(&program, "Name", ()), "Vara Network");
+        assert_eq!(query::<String>(&program, "Symbol", ()), "VARA");
+        assert_eq!(query::<u8>(&program, "Decimals", ()), 12);
+        assert_eq!(
+            query::<Option<U256>>(&program, "Cap", ()),
+            Some(U256::exp10(42))
+        );
+    }
+
+    // # Test case #2.
+    // Storage is empty after init.
+    {
+        assert!(query::<U256>(&program, "TotalSupply", ()).is_zero());
+        assert!(query::<U256>(&program, "BalanceOf", actor(ALICE)).is_zero());
+        assert!(query::<U256>(&program, "Allowance", (actor(ALICE), actor(BOB))).is_zero());
+        assert!(!query::<bool>(&program, "Paused", ()));
+        assert_eq!(query::<u64>(&program, "Nonces", actor(ALICE)), 0);
+    }
+
+    // # Test case #3.
+    // Program panics on unknown method.
+    {
+        let res = program.send_bytes(ALICE, ("Unknown", actor(BOB)).encode());
+        assert!(res.main_failed());
+    }
+}
+
+#[test]
+fn mint_and_burn() {
+    let system = System::new();
+    system.init_logger();
+
+    let program = init(&system, Some(U256::exp10(42)));
+
+    // # Test case #1.
+    // Bob isn't minter, so he can't mint.
+    {
+        let res = send(&program, BOB, "Mint", (actor(BOB), U256::exp10(20)));
+        assert_reply(&res, "Mint", Err::<bool, _>(Error::Unauthorized));
+        assert!(query::<U256>(&program, "TotalSupply", ()).is_zero());
+    }
+
+    // # Test case #2.
+    // Alice mints to Bob.
+    {
+        let res = send(&program, ALICE, "Mint", (actor(BOB), U256::exp10(20)));
+        assert_reply(&res, "Mint", Ok::<_, Error>(true));
+        assert_event(
+            &res,
+            Event::Transfer {
+                from: ActorId::zero(),
+                to: actor(BOB),
+                value: non_zero(U256::exp10(20)),
+            },
+        );
+
+        assert_eq!(
+            query::<U256>(&program, "BalanceOf", actor(BOB)),
+            U256::exp10(20)
+        );
+        assert_eq!(query::<U256>(&program, "TotalSupply", ()), U256::exp10(20));
+    }
+
+    // # Test case #3.
+    // Alice can't mint over the cap.
+    {
+        let res = send(&program, ALICE, "Mint", (actor(BOB), U256::exp10(42)));
+        assert_reply(&res, "Mint", Err::<bool, _>(Error::CapExceeded));
+        assert_eq!(query::<U256>(&program, "TotalSupply", ()), U256::exp10(20));
+    }
+
+    // # Test case #4.
+    // Bob burns part of his balance.
+    {
+        let res = send(&program, BOB, "Burn", (actor(BOB), U256::exp10(19)));
+        assert_reply(&res, "Burn", Ok::<_, Error>(true));
+        assert_event(
+            &res,
+            Event::Transfer {
+                from: actor(BOB),
+                to: ActorId::zero(),
+                value: non_zero(U256::exp10(19)),
+            },
+        );
+
+        assert_eq!(
+            query::<U256>(&program, "TotalSupply", ()),
+            U256::exp10(20) - U256::exp10(19)
+        );
+    }
+
+    // # Test case #5.
+    // Charlie can't burn from Bob with no approval, but Alice as burner can.
+    {
+        let res = send(&program, CHARLIE, "BurnFrom", (actor(BOB), U256::one()));
+        assert_reply(
+            &res,
+            "BurnFrom",
+            Err::<bool, _>(Error::InsufficientAllowance),
+        );
+
+        let res = send(&program, CHARLIE, "Burn", (actor(BOB), U256::one()));
+        assert_reply(&res, "Burn", Err::<bool, _>(Error::Unauthorized));
+
+        let res = send(&program, ALICE, "Burn", (actor(BOB), U256::one()));
+        assert_reply(&res, "Burn", Ok::<_, Error>(true));
+
+        assert_eq!(
+            query::<U256>(&program, "TotalSupply", ()),
+            U256::exp10(20) - U256::exp10(19) - U256::one()
+        );
+    }
+}
+
+#[test]
+fn transfer() {
+    let system = System::new();
+    system.init_logger();
+
+    let program = init(&system, None);
+
+    let res = send(&program, ALICE, "Mint", (actor(ALICE), U256::exp10(42)));
+    assert_reply(&res, "Mint", Ok::<_, Error>(true));
+
+    // # Test case #1.
+    // Alice transfers to Bob.
+    {
+        let res = send(&program, ALICE, "Transfer", (actor(BOB), U256::exp10(20)));
+        assert_reply(&res, "Transfer", Ok::<_, Error>(true));
+        assert_event(
+            &res,
+            Event::Transfer {
+                from: actor(ALICE),
+                to: actor(BOB),
+                value: non_zero(U256::exp10(20)),
+            },
+        );
+
+        assert_eq!(
+            query::<U256>(&program, "BalanceOf", actor(BOB)),
+            U256::exp10(20)
+        );
+    }
+
+    // # Test case #2.
+    // Bob transfers to Charlie more than he has.
+    {
+        let res = send(&program, BOB, "Transfer", (actor(CHARLIE), U256::exp10(21)));
+        assert_reply(&res, "Transfer", Err::<bool, _>(Error::InsufficientBalance));
+
+        assert_eq!(
+            query::<U256>(&program, "BalanceOf", actor(BOB)),
+            U256::exp10(20)
+        );
+        assert!(query::<U256>(&program, "BalanceOf", actor(CHARLIE)).is_zero());
+    }
+
+    // # Test case #3.
+    // Self transfer is noop.
+    {
+        let res = send(&program, BOB, "Transfer", (actor(BOB), U256::exp10(20)));
+        assert_reply(&res, "Transfer", Ok::<_, Error>(false));
+    }
+
+    // # Test case #4.
+    // Alice transfers to Bob and Charlie in batch.
+    {
+        let transfers = vec![
+            (actor(BOB), U256::exp10(20)),
+            (actor(CHARLIE), U256::exp10(20)),
+        ];
+
+        let res = send(&program, ALICE, "BatchTransfer", transfers);
+        assert_reply(&res, "BatchTransfer", Ok::<_, Error>(true));
+        assert_event(
+            &res,
+            Event::Transfer {
+                from: actor(ALICE),
+                to: actor(CHARLIE),
+                value: non_zero(U256::exp10(20)),
+            },
+        );
+
+        assert_eq!(
+            query::<U256>(&program, "BalanceOf", actor(BOB)),
+            U256::exp10(20).saturating_mul(2.into())
+        );
+        assert_eq!(
+            query::<U256>(&program, "BalanceOf", actor(CHARLIE)),
+            U256::exp10(20)
+        );
+    }
+
+    // # Test case #5.
+    // Charlie transfers everything to Bob, so he's no longer listed as holder.
+    {
+        assert_eq!(query::<u32>(&program, "HoldersCount", ()), 3);
+
+        let res = send(&program, CHARLIE, "Transfer", (actor(BOB), U256::exp10(20)));
+        assert_reply(&res, "Transfer", Ok::<_, Error>(true));
+
+        let alice_balance = U256::exp10(42) - U256::exp10(20) * 3;
+        let bob_balance = U256::exp10(20) * 3;
+
+        assert_eq!(query::<u32>(&program, "HoldersCount", ()), 2);
+        assert_eq!(
+            query::<Vec<(ActorId, U256)>>(&program, "Holders", (0u32, 10u32)),
+            vec![(actor(ALICE), alice_balance), (actor(BOB), bob_balance)]
+        );
+        assert_eq!(
+            query::<Vec<(ActorId, U256)>>(&program, "Holders", (1u32, 10u32)),
+            vec![(actor(BOB), bob_balance)]
+        );
+        assert_eq!(
+            query::<Vec<(ActorId, U256)>>(&program, "TopHolders", 1u32),
+            vec![(actor(ALICE), alice_balance)]
+        );
+    }
+}
+
+#[test]
+fn approve_and_from_transfer() {
+    let system = System::new();
+    system.init_logger();
+
+    let program = init(&system, None);
+
+    let res = send(&program, ALICE, "Mint", (actor(ALICE), U256::exp10(42)));
+    assert_reply(&res, "Mint", Ok::<_, Error>(true));
+
+    // # Test case #1.
+    // Bob tries to transfer from Alice with no approval.
+    {
+        let res = send(
+            &program,
+            BOB,
+            "FromTransfer",
+            (actor(ALICE), actor(CHARLIE), U256::exp10(20)),
+        );
+        assert_reply(
+            &res,
+            "FromTransfer",
+            Err::<bool, _>(Error::InsufficientAllowance),
+        );
+    }
+
+    // # Test case #2.
+    // Alice approves Bob.
+    {
+        let res = send(&program, ALICE, "Approve", (actor(BOB), U256::exp10(20)));
+        assert_reply(&res, "Approve", Ok::<_, Error>(true));
+        assert_event(
+            &res,
+            Event::Approval {
+                owner: actor(ALICE),
+                spender: actor(BOB),
+                value: U256::exp10(20),
+            },
+        );
+
+        let res = send(
+            &program,
+            ALICE,
+            "IncreaseAllowance",
+            (actor(BOB), U256::exp10(20)),
+        );
+        assert_reply(&res, "IncreaseAllowance", Ok::<_, Error>(true));
+
+        let res = send(
+            &program,
+            ALICE,
+            "DecreaseAllowance",
+            (actor(BOB), U256::exp10(20)),
+        );
+        assert_reply(&res, "DecreaseAllowance", Ok::<_, Error>(true));
+
+        assert_eq!(
+            query::<U256>(&program, "Allowance", (actor(ALICE), actor(BOB))),
+            U256::exp10(20)
+        );
+    }
+
+    // # Test case #3.
+    // Bob transfers from Alice to Charlie and allowance is removed.
+    {
+        let res = send(
+            &program,
+            BOB,
+            "FromTransfer",
+            (actor(ALICE), actor(CHARLIE), U256::exp10(20)),
+        );
+        assert_reply(&res, "FromTransfer", Ok::<_, Error>(true));
+        assert_event(
+            &res,
+            Event::Transfer {
+                from: actor(ALICE),
+                to: actor(CHARLIE),
+                value: non_zero(U256::exp10(20)),
+            },
+        );
+
+        assert_eq!(
+            query::<U256>(&program, "BalanceOf", actor(CHARLIE)),
+            U256::exp10(20)
+        );
+        assert!(query::<U256>(&program, "Allowance", (actor(ALICE), actor(BOB))).is_zero());
+        assert!(query::<Vec<ActorId>>(&program, "SpendersOf", actor(ALICE)).is_empty());
+    }
+
+    // # Test case #4.
+    // Alice approves Bob and Charlie, so allowances are listed page by page.
+    {
+        let res = send(&program, ALICE, "Approve", (actor(BOB), U256::exp10(20)));
+        assert_reply(&res, "Approve", Ok::<_, Error>(true));
+
+        let res = send(
+            &program,
+            ALICE,
+            "Approve",
+            (actor(CHARLIE), U256::exp10(10)),
+        );
+        assert_reply(&res, "Approve", Ok::<_, Error>(true));
+
+        assert_eq!(
+            query::<Vec<ActorId>>(&program, "SpendersOf", actor(ALICE)),
+            vec![actor(BOB), actor(CHARLIE)]
+        );
+        assert_eq!(
+            query::<Vec<(ActorId, U256)>>(&program, "AllowancesOf", (actor(ALICE), 0u32, 1u32)),
+            vec![(actor(BOB), U256::exp10(20))]
+        );
+        assert_eq!(
+            query::<Vec<(ActorId, U256)>>(&program, "AllowancesOf", (actor(ALICE), 1u32, 1u32)),
+            vec![(actor(CHARLIE), U256::exp10(10))]
+        );
+    }
+
+    // # Test case #5.
+    // Alice revokes all her allowances at once.
+    {
+        let res = send(&program, ALICE, "RevokeAll", ());
+        assert_reply(&res, "RevokeAll", Ok::<_, Error>(true));
+        assert_event(
+            &res,
+            Event::Approval {
+                owner: actor(ALICE),
+                spender: actor(CHARLIE),
+                value: U256::zero(),
+            },
+        );
+
+        assert!(query::<Vec<ActorId>>(&program, "SpendersOf", actor(ALICE)).is_empty());
+        assert!(query::<U256>(&program, "Allowance", (actor(ALICE), actor(CHARLIE))).is_zero());
+
+        let res = send(&program, ALICE, "RevokeAll", ());
+        assert_reply(&res, "RevokeAll", Ok::<_, Error>(false));
+    }
+}
+
+#[test]
+fn permit() {
+    let system = System::new();
+    system.init_logger();
+
+    let program = init(&system, None);
+
+    let key = ed25519_dalek::SigningKey::from_bytes(&[1; 32]);
+    let owner = ActorId::from(key.verifying_key().to_bytes());
+
+    let message = Permit {
+        domain_separator: query(&program, "DomainSeparator", ()),
+        owner,
+        spender: actor(BOB),
+        value: U256::exp10(20),
+        nonce: query(&program, "Nonces", owner),
+        deadline: u64::MAX,
+    };
+
+    let signature = Signature::Ed25519(key.sign(&message.encode()).to_bytes());
+
+    // # Test case #1.
+    // Permit signed by owner is applied by anyone.
+    {
+        let res = send(
+            &program,
+            CHARLIE,
+            "Permit",
+            (owner, actor(BOB), U256::exp10(20), u64::MAX, signature),
+        );
+        assert_reply(&res, "Permit", Ok::<_, Error>(true));
+
+        assert_eq!(
+            query::<U256>(&program, "Allowance", (owner, actor(BOB))),
+            U256::exp10(20)
+        );
+        assert_eq!(query::<u64>(&program, "Nonces", owner), 1);
+    }
+
+    // # Test case #2.
+    // Permit can't be replayed.
+    {
+        let res = send(
+            &program,
+            CHARLIE,
+            "Permit",
+            (owner, actor(BOB), U256::exp10(20), u64::MAX, signature),
+        );
+        assert_reply(&res, "Permit", Err::<bool, _>(Error::InvalidSignature));
+    }
+}
+
+#[test]
+fn deposit_and_withdraw() {
+    const VALUE: u128 = 10_000_000_000_000;
+
+    let system = System::new();
+    system.init_logger();
+
+    let program = init(&system, None);
+
+    system.mint_to(BOB, 2 * VALUE);
+
+    // # Test case #1.
+    // Bob deposits value and receives the same amount of tokens.
+    {
+        let res = send_with_value(&program, BOB, "Deposit", (), VALUE);
+        assert_reply(&res, "Deposit", Ok::<_, Error>(true));
+        assert_event(
+            &res,
+            Event::Deposit {
+                account: actor(BOB),
+                value: non_zero(VALUE.into()),
+            },
+        );
+
+        assert_eq!(
+            query::<U256>(&program, "BalanceOf", actor(BOB)),
+            VALUE.into()
+        );
+        assert_eq!(query::<U256>(&program, "TotalSupply", ()), VALUE.into());
+    }
+
+    // # Test case #2.
+    // Bob can't withdraw more than he holds.
+    {
+        let res = send(&program, BOB, "Withdraw", U256::from(VALUE) + 1);
+        assert_reply(&res, "Withdraw", Err::<bool, _>(Error::InsufficientBalance));
+    }
+
+    // # Test case #3.
+    // Bob withdraws part of his tokens.
+    {
+        let res = send(&program, BOB, "Withdraw", U256::from(VALUE / 2));
+        assert_reply(&res, "Withdraw", Ok::<_, Error>(true));
+        assert_event(
+            &res,
+            Event::Withdrawal {
+                account: actor(BOB),
+                value: non_zero((VALUE / 2).into()),
+            },
+        );
+
+        assert_eq!(
+            query::<U256>(&program, "BalanceOf", actor(BOB)),
+            (VALUE / 2).into()
+        );
+        assert_eq!(
+            query::<U256>(&program, "TotalSupply", ()),
+            (VALUE / 2).into()
+        );
+
+        let balance = system.balance_of(BOB);
+        claim_value(&system, &program, BOB);
+        assert_eq!(system.balance_of(BOB), balance + VALUE / 2);
+    }
+
+    // # Test case #4.
+    // Deposit fails while minting is paused, so no tokens issued and value is returned.
+    {
+        let res = send(&program, ALICE, "PauseOperation", Operation::Mint);
+        assert_reply(&res, "PauseOperation", Ok::<_, Error>(true));
+
+        let res = send_with_value(&program, BOB, "Deposit", (), VALUE);
+        assert_reply(&res, "Deposit", Err::<bool, _>(Error::Paused));
+
+        assert_eq!(
+            query::<U256>(&program, "TotalSupply", ()),
+            (VALUE / 2).into()
+        );
+
+        let balance = system.balance_of(BOB);
+        claim_value(&system, &program, BOB);
+        assert_eq!(system.balance_of(BOB), balance + VALUE);
+    }
+
+    // # Test case #5.
+    // Program holds less value than withdrawn, so sending fails and tokens are kept.
+    {
+        let res = send(&program, ALICE, "UnpauseOperation", Operation::Mint);
+        assert_reply(&res, "UnpauseOperation", Ok::<_, Error>(true));
+
+        let res = send(&program, ALICE, "Mint", (actor(ALICE), U256::from(VALUE)));
+        assert_reply(&res, "Mint", Ok::<_, Error>(true));
+
+        let res = send(&program, ALICE, "Withdraw", U256::from(VALUE));
+        assert_reply(&res, "Withdraw", Err::<bool, _>(Error::ValueSendFailed));
+
+        assert_eq!(
+            query::<U256>(&program, "BalanceOf", actor(ALICE)),
+            VALUE.into()
+        );
+        assert_eq!(
+            query::<U256>(&program, "TotalSupply", ()),
+            (VALUE + VALUE / 2).into()
+        );
+    }
+}
+
+#[test]
+fn transfer_and_call() {
+    const ACCEPTOR: u64 = 2;
+    const REJECTOR: u64 = 3;
+
+    let system = System::new();
+    system.init_logger();
+
+    let program = init(&system, None);
+
+    for (id, accept) in [(ACCEPTOR, true), (REJECTOR, false)] {
+        let receiver = Program::mock_with_id(&system, id, Receiver { accept });
+        assert!(!receiver.send_bytes(ALICE, b"").main_failed());
+    }
+
+    let res = send(&program, ALICE, "Mint", (actor(BOB), U256::exp10(20)));
+    assert_reply(&res, "Mint", Ok::<_, Error>(true));
+
+    // # Test case #1.
+    // Receiver accepts tokens and keeps them.
+    {
+        let args = (actor(ACCEPTOR), U256::exp10(19), b"data".to_vec());
+        let res = send(&program, BOB, "TransferAndCall", args);
+        assert_reply(&res, "TransferAndCall", Ok::<_, Error>(true));
+
+        assert_eq!(
+            query::<U256>(&program, "BalanceOf", actor(ACCEPTOR)),
+            U256::exp10(19)
+        );
+    }
+
+    // # Test case #2.
+    // Receiver rejects tokens, so escrowed ones are returned.
+    {
+        let args = (actor(REJECTOR), U256::exp10(19), b"data".to_vec());
+        let res = send(&program, BOB, "TransferAndCall", args);
+        assert_reply(
+            &res,
+            "TransferAndCall",
+            Err::<bool, _>(Error::ReceiverRejected),
+        );
+
+        assert!(query::<U256>(&program, "BalanceOf", actor(REJECTOR)).is_zero());
+        assert_eq!(
+            query::<U256>(&program, "BalanceOf", actor(BOB)),
+            U256::exp10(20) - U256::exp10(19)
+        );
+    }
+
+    // # Test case #3.
+    // Spender rejects approval, so it's reverted.
+    {
+        let args = (actor(REJECTOR), U256::exp10(19), Vec::<u8>::new());
+        let res = send(&program, BOB, "ApproveAndCall", args);
+        assert_reply(
+            &res,
+            "ApproveAndCall",
+            Err::<bool, _>(Error::ReceiverRejected),
+        );
+
+        assert!(query::<U256>(&program, "Allowance", (actor(BOB), actor(REJECTOR))).is_zero());
+    }
+
+    // # Test case #4.
+    // Spender accepts approval.
+    {
+        let args = (actor(ACCEPTOR), U256::exp10(19), Vec::<u8>::new());
+        let res = send(&program, BOB, "ApproveAndCall", args);
+        assert_reply(&res, "ApproveAndCall", Ok::<_, Error>(true));
+
+        assert_eq!(
+            query::<U256>(&program, "Allowance", (actor(BOB), actor(ACCEPTOR))),
+            U256::exp10(19)
+        );
+    }
+}
+
+#[test]
+fn snapshot() {
+    let system = System::new();
+    system.init_logger();
+
+    let program = init(&system, None);
+
+    let res = send(&program, ALICE, "Mint", (actor(BOB), U256::exp10(20)));
+    assert_reply(&res, "Mint", Ok::<_, Error>(true));
+
+    // # Test case #1.
+    // Bob isn't admin, so he can't take snapshots.
+    {
+        let res = send(&program, BOB, "Snapshot", ());
+        assert_reply(&res, "Snapshot", Err::<u64, _>(Error::Unauthorized));
+    }
+
+    // # Test case #2.
+    // Alice takes snapshot, then balances change.
+    {
+        let res = send(&program, ALICE, "Snapshot", ());
+        assert_event(&res, Event::Snapshot { id: 1 });
+
+        let res = send(&program, BOB, "Transfer", (actor(CHARLIE), U256::exp10(19)));
+        assert_reply(&res, "Transfer", Ok::<_, Error>(true));
+
+        let res = send(&program, ALICE, "Mint", (actor(CHARLIE), U256::exp10(19)));
+        assert_reply(&res, "Mint", Ok::<_, Error>(true));
+    }
+
+    // # Test case #3.
+    // Snapshot keeps values at the moment it was taken.
+    {
+        assert_eq!(
+            query::<Result<U256, Error>>(&program, "BalanceOfAt", (actor(BOB), 1u64)),
+            Ok(U256::exp10(20))
+        );
+        assert_eq!(
+            query::<Result<U256, Error>>(&program, "BalanceOfAt", (actor(CHARLIE), 1u64)),
+            Ok(U256::zero())
+        );
+        assert_eq!(
+            query::<Result<U256, Error>>(&program, "TotalSupplyAt", 1u64),
+            Ok(U256::exp10(20))
+        );
+        assert_eq!(
+            query::<Result<U256, Error>>(&program, "TotalSupplyAt", 2u64),
+            Err(Error::SnapshotNotFound)
+        );
+    }
+}
+
+#[test]
+fn delegate() {
+    let system = System::new();
+    system.init_logger();
+
+    let program = init(&system, None);
+
+    let res = send(&program, ALICE, "Mint", (actor(BOB), U256::exp10(20)));
+    assert_reply(&res, "Mint", Ok::<_, Error>(true));
+
+    // # Test case #1.
+    // Bob delegates to Charlie, who receives his votes.
+    {
+        let res = send(&program, BOB, "Delegate", actor(CHARLIE));
+        assert_reply(&res, "Delegate", Ok::<_, Error>(true));
+        assert_event(
+            &res,
+            Event::DelegateChanged {
+                delegator: actor(BOB),
+                from_delegate: ActorId::zero(),
+                to_delegate: actor(CHARLIE),
+            },
+        );
+        assert_event(
+            &res,
+            Event::DelegateVotesChanged {
+                delegate: actor(CHARLIE),
+                previous_votes: U256::zero(),
+                new_votes: U256::exp10(20),
+            },
+        );
+
+        assert_eq!(
+            query::<ActorId>(&program, "Delegates", actor(BOB)),
+            actor(CHARLIE)
+        );
+        assert_eq!(
+            query::<U256>(&program, "GetVotes", actor(CHARLIE)),
+            U256::exp10(20)
+        );
+    }
+
+    // # Test case #2.
+    // Votes follow transferred tokens.
+    {
+        let res = send(&program, BOB, "Transfer", (actor(ALICE), U256::exp10(19)));
+        assert_reply(&res, "Transfer", Ok::<_, Error>(true));
+
+        assert_eq!(
+            query::<U256>(&program, "GetVotes", actor(CHARLIE)),
+            U256::exp10(20) - U256::exp10(19)
+        );
+    }
+
+    // # Test case #3.
+    // Votes can't be looked up for future blocks.
+    {
+        assert_eq!(
+            query::<Result<U256, Error>>(&program, "GetPastVotes", (actor(CHARLIE), u32::MAX)),
+            Err(Error::FutureLookup)
+        );
+    }
+}
+
+#[test]
+fn transfer_locked() {
+    let system = System::new();
+    system.init_logger();
+
+    let program = init(&system, None);
+
+    let res = send(&program, ALICE, "Mint", (actor(BOB), U256::exp10(20)));
+    assert_reply(&res, "Mint", Ok::<_, Error>(true));
+
+    // # Test case #1.
+    // Bob transfers tokens to Charlie locked forever.
+    {
+        let args = (actor(CHARLIE), U256::exp10(19), u32::MAX);
+        let res = send(&program, BOB, "TransferLocked", args);
+        assert_reply(&res, "TransferLocked", Ok::<_, Error>(true));
+        assert_event(
+            &res,
+            Event::Locked {
+                account: actor(CHARLIE),
+                value: non_zero(U256::exp10(19)),
+                until: u32::MAX,
+            },
+        );
+
+        assert_eq!(
+            query::<U256>(&program, "BalanceOf", actor(CHARLIE)),
+            U256::exp10(19)
+        );
+        assert_eq!(
+            query::<U256>(&program, "LockedBalanceOf", actor(CHARLIE)),
+            U256::exp10(19)
+        );
+    }
+
+    // # Test case #2.
+    // Charlie can't spend locked tokens.
+    {
+        let res = send(&program, CHARLIE, "Transfer", (actor(BOB), U256::one()));
+        assert_reply(&res, "Transfer", Err::<bool, _>(Error::BalanceLocked));
+
+        let res = send(&program, CHARLIE, "Burn", (actor(CHARLIE), U256::one()));
+        assert_reply(&res, "Burn", Err::<bool, _>(Error::BalanceLocked));
+    }
+
+    // # Test case #3.
+    // Bob locks his own tokens, but can spend the rest.
+    {
+        let res = send(&program, BOB, "Lock", (U256::exp10(19), u32::MAX));
+        assert_reply(&res, "Lock", Ok::<_, Error>(true));
+
+        let free = U256::exp10(20) - U256::exp10(19) * 2;
+
+        let res = send(&program, BOB, "Transfer", (actor(ALICE), free + 1));
+        assert_reply(&res, "Transfer", Err::<bool, _>(Error::BalanceLocked));
+
+        let res = send(&program, BOB, "Transfer", (actor(ALICE), free));
+        assert_reply(&res, "Transfer", Ok::<_, Error>(true));
+    }
+}
+
+#[test]
+fn export_and_import_state() {
+    let system = System::new();
+    system.init_logger();
+
+    let program = init(&system, None);
+
+    for (to, value) in [(ALICE, U256::exp10(42)), (BOB, U256::exp10(20))] {
+        let res = send(&program, ALICE, "Mint", (actor(to), value));
+        assert_reply(&res, "Mint", Ok::<_, Error>(true));
+    }
+
+    let res = send(&program, ALICE, "Approve", (actor(BOB), U256::exp10(10)));
+    assert_reply(&res, "Approve", Ok::<_, Error>(true));
+
+    // # Test case #1.
+    // Only admin exports state.
+    {
+        let res = send(&program, BOB, "ExportState", (0u32, 1u32));
+        assert_reply(
+            &res,
+            "ExportState",
+            Err::<StateChunk, _>(Error::Unauthorized),
+        );
+    }
+
+    let first = query::<Result<StateChunk, Error>>(&program, "ExportState", (0u32, 1u32))
+        .expect("Alice is admin");
+    let last = query::<Result<StateChunk, Error>>(&program, "ExportState", (1u32, 1u32))
+        .expect("Alice is admin");
+
+    assert_eq!(first.next, Some(1));
+    assert_eq!(last.next, None);
+
+    // # Test case #2.
+    // New program is deployed from the first chunk and stays paused.
+    let migrated = Program::from_binary_with_id(&system, 2, erc20_wasm::WASM_BINARY);
+    {
+        let res = migrated.send_bytes(ALICE, ("FromState", first).encode());
+        assert!(!res.main_failed());
+
+        assert!(query::<bool>(&migrated, "Paused", ()));
+        assert_eq!(query::<u32>(&migrated, "HoldersCount", ()), 1);
+    }
+
+    // # Test case #3.
+    // Delegation waits for import to complete.
+    {
+        let res = send(&migrated, ALICE, "Delegate", actor(BOB));
+        assert_reply(&res, "Delegate", Err::<bool, _>(Error::Paused));
+    }
+
+    // # Test case #4.
+    // The rest is imported, so state matches original one.
+    {
+        let res = send(&migrated, ALICE, "ImportState", last.clone());
+        assert_reply(&res, "ImportState", Ok::<_, Error>(true));
+
+        assert!(!query::<bool>(&migrated, "Paused", ()));
+        assert_eq!(
+            query::<U256>(&migrated, "TotalSupply", ()),
+            U256::exp10(42) + U256::exp10(20)
+        );
+        assert_eq!(
+            query::<U256>(&migrated, "BalanceOf", actor(BOB)),
+            U256::exp10(20)
+        );
+        assert_eq!(
+            query::<U256>(&migrated, "Allowance", (actor(ALICE), actor(BOB))),
+            U256::exp10(10)
+        );
+    }
+
+    // # Test case #5.
+    // Import is closed once complete.
+    {
+        let res = send(&migrated, ALICE, "ImportState", last);
+        assert_reply(&res, "ImportState", Err::<bool, _>(Error::ImportClosed));
+    }
+}
+
+#[test]
+fn pause_and_freeze() {
+    let system = System::new();
+    system.init_logger();
+
+    let program = init(&system, None);
+
+    let res = send(&program, ALICE, "Mint", (actor(BOB), U256::exp10(42)));
+    assert_reply(&res, "Mint", Ok::<_, Error>(true));
+
+    // # Test case #1.
+    // Bob isn't pauser, so he can't pause.
+    {
+        let res = send(&program, BOB, "Pause", ());
+        assert_reply(&res, "Pause", Err::<bool, _>(Error::Unauthorized));
+    }
+
+    // # Test case #2.
+    // Transfers are paused, while approvals aren't.
+    {
+        let res = send(&program, ALICE, "PauseOperation", Operation::Transfer);
+        assert_reply(&res, "PauseOperation", Ok::<_, Error>(true));
+        assert_event(
+            &res,
+            Event::Paused {
+                operation: Some(Operation::Transfer),
+            },
+        );
+
+        assert!(query::<bool>(&program, "IsPaused", Operation::Transfer));
+        assert!(!query::<bool>(&program, "IsPaused", Operation::Approve));
+
+        let res = send(&program, BOB, "Transfer", (actor(CHARLIE), U256::one()));
+        assert_reply(&res, "Transfer", Err::<bool, _>(Error::Paused));
+
+        let res = send(&program, BOB, "Approve", (actor(CHARLIE), U256::one()));
+        assert_reply(&res, "Approve", Ok::<_, Error>(true));
+
+        let res = send(&program, ALICE, "UnpauseOperation", Operation::Transfer);
+        assert_reply(&res, "UnpauseOperation", Ok::<_, Error>(true));
+    }
+
+    // # Test case #3.
+    // Everything is paused globally.
+    {
+        let res = send(&program, ALICE, "Pause", ());
+        assert_reply(&res, "Pause", Ok::<_, Error>(true));
+        assert!(query::<bool>(&program, "Paused", ()));
+
+        let res = send(&program, BOB, "Approve", (actor(CHARLIE), U256::zero()));
+        assert_reply(&res, "Approve", Err::<bool, _>(Error::Paused));
+
+        let res = send(&program, ALICE, "Unpause", ());
+        assert_reply(&res, "Unpause", Ok::<_, Error>(true));
+        assert!(!query::<bool>(&program, "Paused", ()));
+    }
+
+    // # Test case #4.
+    // Frozen Bob can't send tokens and his balance is seized.
+    {
+        let res = send(&program, ALICE, "Freeze", actor(BOB));
+        assert_reply(&res, "Freeze", Ok::<_, Error>(true));
+        assert!(query::<bool>(&program, "IsFrozen", actor(BOB)));
+
+        let res = send(&program, BOB, "Transfer", (actor(CHARLIE), U256::one()));
+        assert_reply(&res, "Transfer", Err::<bool, _>(Error::AccountFrozen));
+
+        let res = send(&program, ALICE, "Seize", (actor(BOB), actor(ALICE)));
+        assert_reply(&res, "Seize", Ok::<_, Error>(true));
+        assert_event(
+            &res,
+            Event::Seized {
+                from: actor(BOB),
+                to: actor(ALICE),
+                value: non_zero(U256::exp10(42)),
+            },
+        );
+
+        let res = send(&program, ALICE, "Unfreeze", actor(BOB));
+        assert_reply(&res, "Unfreeze", Ok::<_, Error>(true));
+        assert!(!query::<bool>(&program, "IsFrozen", actor(BOB)));
+    }
+}
+
+// Mock of the program implementing `Erc20Receiver` service.
+#[derive(Debug)]
+struct Receiver {
+    accept: bool,
+}
+
+impl WasmProgram for Receiver {
+    fn init(&mut self, _payload: Vec<u8>) -> Result<Option<Vec<u8>>, &'static str> {
+        Ok(None)
+    }
+
+    fn handle(&mut self, payload: Vec<u8>) -> Result<Option<Vec<u8>>, &'static str> {
+        let (service, method, _owner, _value, _data) =
+            <(String, String, ActorId, U256, Vec<u8>)>::decode_all(&mut payload.as_slice())
+                .map_err(|_| "Unexpected payload")?;
+
+        Ok(Some((service, method, self.accept).encode()))
+    }
+
+    // Receiver sends no messages, so it never gets replies.
+    fn handle_reply(&mut self, _payload: Vec<u8>) -> Result<(), &'static str> {
+        Err("Unexpected reply")
+    }
+
+    fn handle_signal(&mut self, _payload: Vec<u8>) -> Result<(), &'static str> {
+        Ok(())
+    }
+
+    fn state(&mut self) -> Result<Vec<u8>, &'static str> {
+        Err("Receiver has no state")
+    }
+}
+
+fn init(system: &System, cap: Option<U256>) -> Program<'_> {
+    let program = Program::from_binary_with_id(system, 1, erc20_wasm::WASM_BINARY);
+
+    let res = program.send_bytes(ALICE, ("New", "Vara Network", "VARA", 12u8, cap).encode());
+    assert!(!res.main_failed());
+
+    program
+}
+
+fn send(program: &Program, from: u64, method: &str, args: impl Encode) -> RunResult {
+    send_with_value(program, from, method, args, 0)
+}
+
+fn send_with_value(
+    program: &Program,
+    from: u64,
+    method: &str,
+    args: impl Encode,
+    value: u128,
+) -> RunResult {
+    let mut payload = method.encode();
+    args.encode_to(&mut payload);
+
+    let res = program.send_bytes_with_value(from, payload, value);
+    assert!(!res.main_failed());
+
+    res
+}
+
+fn query<T: Decode>(program: &Program, method: &str, args: impl Encode) -> T {
+    let res = send(program, ALICE, method, args);
+
+    res.log()
+        .iter()
+        .find_map(|log| {
+            let (name, value) = <(String, T)>::decode_all(&mut log.payload()).ok()?;
+            (name == method).then_some(value)
+        })
+        .expect("Reply not found")
+}
+
+fn assert_reply<T: Encode>(res: &RunResult, method: &str, value: Result<T, Error>) {
+    let payload = (method, value).encode();
+
+    assert!(res.log().iter().any(|log| log.payload() == payload));
+}
+
+fn assert_event(res: &RunResult, event: Event) {
+    let encoded = event.encode();
+
+    assert!(res
+        .log()
+        .iter()
+        .any(|log| log.payload().ends_with(&encoded)));
+}
+
+// Value sent to user is held in mailbox until claimed.
+fn claim_value(system: &System, program: &Program, to: u64) {
+    let mailbox = system.get_mailbox(to);
+    let log = Log::builder().source(program.id()).dest(to);
+
+    assert!(mailbox.contains(&log));
+    mailbox.claim_value(log);
+}
+
+fn actor(id: u64) -> ActorId {
+    id.into()
+}
+
+fn non_zero(value: U256) -> NonZeroU256 {
+    value.try_into().unwrap()
+}